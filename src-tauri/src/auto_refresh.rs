@@ -0,0 +1,110 @@
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::watch;
+use tracing::{debug, warn};
+
+pub const REFRESH_TICK_EVENT: &str = "refresh-tick";
+
+// The location the frontend is showing; sent back with every tick so a late tick
+// for a view the user already left can be ignored
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActiveView {
+    pub connection_name: String,
+    pub bucket: String,
+    pub prefix: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct RefreshState {
+    enabled: bool,
+    interval_secs: u64,
+    paused: bool,
+    view: Option<ActiveView>,
+}
+
+impl RefreshState {
+    // The view to tick for, or None while auto-refresh is off, paused or nothing is open
+    fn ticking_view(&self) -> Option<&ActiveView> {
+        if self.enabled && !self.paused && self.interval_secs > 0 {
+            self.view.as_ref()
+        } else {
+            None
+        }
+    }
+}
+
+static STATE: OnceLock<watch::Sender<RefreshState>> = OnceLock::new();
+
+fn state() -> &'static watch::Sender<RefreshState> {
+    STATE.get_or_init(|| watch::channel(RefreshState::default()).0)
+}
+
+// Starts the timer task. Every change to the state restarts the wait, so a new
+// interval or view takes effect right away.
+pub fn spawn(app: AppHandle) {
+    let mut changes = state().subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let current = changes.borrow_and_update().clone();
+            let Some(view) = current.ticking_view() else {
+                if changes.changed().await.is_err() {
+                    return;
+                }
+                continue;
+            };
+
+            tokio::select! {
+                _ = tokio::time::sleep(Duration::from_secs(current.interval_secs)) => {
+                    if let Err(e) = app.emit(REFRESH_TICK_EVENT, view.clone()) {
+                        warn!("Failed to emit refresh tick: {}", e);
+                    }
+                }
+                changed = changes.changed() => {
+                    if changed.is_err() {
+                        return;
+                    }
+                }
+            }
+        }
+    });
+}
+
+// Applied whenever general settings are loaded or saved
+pub fn configure(enabled: bool, interval_secs: u32) {
+    state().send_if_modified(|state| {
+        let changed = state.enabled != enabled || state.interval_secs != u64::from(interval_secs);
+        state.enabled = enabled;
+        state.interval_secs = u64::from(interval_secs);
+        changed
+    });
+}
+
+pub fn set_active_view(view: Option<ActiveView>) {
+    debug!("Active view changed to {:?}", view);
+    state().send_if_modified(|state| {
+        let changed = state.view != view;
+        state.view = view;
+        changed
+    });
+}
+
+// Stops ticking for a connection that was disconnected
+pub fn clear_connection(connection_name: &str) {
+    state().send_if_modified(|state| {
+        let is_active = state.view.as_ref().is_some_and(|view| view.connection_name == connection_name);
+        if is_active {
+            state.view = None;
+        }
+        is_active
+    });
+}
+
+pub fn set_paused(paused: bool) {
+    state().send_if_modified(|state| {
+        let changed = state.paused != paused;
+        state.paused = paused;
+        changed
+    });
+}
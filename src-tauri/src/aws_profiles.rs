@@ -0,0 +1,247 @@
+use crate::settings::{ConnectionConfig, ConnectionStats};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+const DEFAULT_REGION: &str = "us-east-1";
+
+// Keys that mean the profile gets its credentials from somewhere only the SDK can follow
+const INDIRECT_CREDENTIAL_KEYS: &[&str] = &[
+    "sso_start_url",
+    "sso_session",
+    "role_arn",
+    "source_profile",
+    "credential_source",
+    "credential_process",
+    "web_identity_token_file",
+];
+
+type Profiles = BTreeMap<String, BTreeMap<String, String>>;
+
+// Reads the AWS CLI credentials and config files and turns every profile into a connection.
+// Profiles with static keys get them copied; SSO, role and process profiles are left to
+// the default credential chain. `credentials_path` overrides the credentials file; the
+// config file is then looked up next to it.
+pub fn import_aws_profiles(credentials_path: Option<String>) -> Result<Vec<ConnectionConfig>, String> {
+    let (credentials_file, config_file) = match credentials_path {
+        Some(path) => {
+            let credentials_file = PathBuf::from(path);
+            let config_file = credentials_file.with_file_name("config");
+            (credentials_file, config_file)
+        }
+        None => default_paths()?,
+    };
+
+    if !credentials_file.exists() && !config_file.exists() {
+        return Err(format!("No AWS credentials found at {}", credentials_file.display()));
+    }
+
+    let credentials = read_profiles(&credentials_file, false)?;
+    let config = read_profiles(&config_file, true)?;
+
+    // Values from the credentials file win over the config file, as in the AWS CLI
+    let mut merged: Profiles = config;
+    for (name, values) in credentials {
+        merged.entry(name).or_default().extend(values);
+    }
+
+    let mut connections = Vec::new();
+    for (name, values) in merged {
+        let access_key = values.get("aws_access_key_id").filter(|v| !v.is_empty());
+        let secret_key = values.get("aws_secret_access_key").filter(|v| !v.is_empty());
+        let (access_key, secret_key, use_default_credential_chain) = match (access_key, secret_key) {
+            (Some(access_key), Some(secret_key)) => (access_key.clone(), secret_key.clone(), false),
+            _ if INDIRECT_CREDENTIAL_KEYS.iter().any(|key| values.contains_key(*key)) => {
+                (String::new(), String::new(), true)
+            }
+            _ => {
+                warn!("Skipping AWS profile '{}': no access key configured", name);
+                continue;
+            }
+        };
+
+        let region = values
+            .get("region")
+            .filter(|v| !v.is_empty())
+            .cloned()
+            .unwrap_or_else(|| DEFAULT_REGION.to_string());
+        let endpoint = values
+            .get("s3.endpoint_url")
+            .or_else(|| values.get("endpoint_url"))
+            .cloned()
+            .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+        let force_path_style = values.get("s3.addressing_style").and_then(|style| match style.as_str() {
+            "path" => Some(true),
+            "virtual" => Some(false),
+            _ => None,
+        });
+
+        connections.push(ConnectionConfig {
+            profile_name: use_default_credential_chain.then(|| name.clone()),
+            name,
+            service_type: "Amazon S3".to_string(),
+            endpoint,
+            access_key,
+            secret_key,
+            region,
+            is_default: false,
+            force_path_style,
+            allow_invalid_certs: false,
+            session_token: values.get("aws_session_token").filter(|v| !v.is_empty()).cloned(),
+            expiration: None,
+            timeout_secs: None,
+            max_retries: None,
+            proxy_url: None,
+            max_connections: None,
+            fallback_endpoints: Vec::new(),
+            anonymous: false,
+            request_payer: false,
+            use_accelerate: false,
+            use_dualstack: false,
+            use_default_credential_chain,
+            read_only: false,
+            stats: ConnectionStats::default(),
+        });
+    }
+
+    Ok(connections)
+}
+
+// Honours the same environment overrides as the AWS CLI before falling back to ~/.aws
+fn default_paths() -> Result<(PathBuf, PathBuf), String> {
+    let aws_dir = dirs::home_dir()
+        .map(|home| home.join(".aws"))
+        .ok_or("Could not determine home directory")?;
+    let from_env = |var: &str, file: &str| {
+        std::env::var_os(var)
+            .filter(|value| !value.is_empty())
+            .map(PathBuf::from)
+            .unwrap_or_else(|| aws_dir.join(file))
+    };
+    Ok((
+        from_env("AWS_SHARED_CREDENTIALS_FILE", "credentials"),
+        from_env("AWS_CONFIG_FILE", "config"),
+    ))
+}
+
+fn read_profiles(path: &Path, is_config: bool) -> Result<Profiles, String> {
+    if !path.exists() {
+        return Ok(Profiles::new());
+    }
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(parse_profiles(&content, is_config))
+}
+
+// Minimal INI parser for the AWS file format. The config file names sections
+// "[profile name]" (except "[default]"), and indented lines below an empty key form a
+// nested block such as "s3 =\n  endpoint_url = ...", stored here as "s3.endpoint_url".
+fn parse_profiles(content: &str, is_config: bool) -> Profiles {
+    let mut profiles = Profiles::new();
+    let mut current: Option<String> = None;
+    let mut nested: Option<String> = None;
+
+    for raw_line in content.lines() {
+        let line = raw_line.trim();
+        if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+            continue;
+        }
+
+        if let Some(section) = line.strip_prefix('[').and_then(|rest| rest.strip_suffix(']')) {
+            let section = section.trim();
+            current = if !is_config || section == "default" {
+                Some(section.to_string())
+            } else {
+                // Skips [sso-session ...], [services ...] and other non-profile sections
+                section.strip_prefix("profile ").map(|name| name.trim().to_string())
+            };
+            nested = None;
+            continue;
+        }
+
+        let Some(profile) = current.as_ref() else { continue };
+        let Some((key, value)) = line.split_once('=') else { continue };
+        let key = key.trim().to_lowercase();
+        let value = value.trim().to_string();
+        let is_indented = raw_line.starts_with(char::is_whitespace);
+
+        let key = match (&nested, is_indented) {
+            (Some(parent), true) => format!("{}.{}", parent, key),
+            _ if value.is_empty() => {
+                nested = Some(key);
+                continue;
+            }
+            _ => {
+                nested = None;
+                key
+            }
+        };
+        profiles.entry(profile.clone()).or_default().insert(key, value);
+    }
+
+    profiles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_sections_need_the_profile_prefix() {
+        let content = "[default]\nregion = eu-west-1\n\n[profile dev]\nregion = us-west-2\n\n[sso-session corp]\nsso_region = us-east-1\n";
+
+        let config = parse_profiles(content, true);
+        assert_eq!(config.keys().collect::<Vec<_>>(), ["default", "dev"]);
+        assert_eq!(config["dev"]["region"], "us-west-2");
+
+        // The credentials file uses bare profile names
+        let credentials = parse_profiles("[dev]\naws_access_key_id = AKID\n", false);
+        assert_eq!(credentials["dev"]["aws_access_key_id"], "AKID");
+    }
+
+    #[test]
+    fn skips_comments_and_nests_indented_blocks() {
+        let content = "# comment\n[profile minio]\n; another comment\nS3 =\n  endpoint_url = http://localhost:9000\n  addressing_style = path\nregion = us-east-1\n";
+
+        let profiles = parse_profiles(content, true);
+        let minio = &profiles["minio"];
+        assert_eq!(minio["s3.endpoint_url"], "http://localhost:9000");
+        assert_eq!(minio["s3.addressing_style"], "path");
+        assert_eq!(minio["region"], "us-east-1");
+        assert_eq!(minio.len(), 3);
+    }
+
+    #[test]
+    fn merges_credentials_over_config() {
+        let dir = std::env::temp_dir().join(format!("bucketviewer-aws-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let credentials_path = dir.join("credentials");
+        std::fs::write(&credentials_path, "[default]\naws_access_key_id = AKID\naws_secret_access_key = SECRET\n").unwrap();
+        std::fs::write(
+            dir.join("config"),
+            "[default]\nregion = eu-west-1\naws_access_key_id = STALE\n\n[profile sso]\nsso_session = corp\n\n[profile empty]\nregion = us-east-1\n",
+        )
+        .unwrap();
+
+        let connections = import_aws_profiles(Some(credentials_path.to_string_lossy().to_string())).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        let default = connections.iter().find(|conn| conn.name == "default").unwrap();
+        assert_eq!(default.access_key, "AKID");
+        assert_eq!(default.secret_key, "SECRET");
+        assert_eq!(default.region, "eu-west-1");
+        assert_eq!(default.endpoint, "https://s3.eu-west-1.amazonaws.com");
+        let sso = connections.iter().find(|conn| conn.name == "sso").unwrap();
+        assert!(sso.use_default_credential_chain);
+        assert_eq!(sso.profile_name.as_deref(), Some("sso"));
+        assert!(!connections.iter().any(|conn| conn.name == "empty"));
+    }
+
+    #[test]
+    fn missing_files_are_reported() {
+        let dir = std::env::temp_dir().join(format!("bucketviewer-aws-{}", uuid::Uuid::new_v4()));
+
+        assert!(read_profiles(&dir.join("config"), true).unwrap().is_empty());
+        assert!(import_aws_profiles(Some(dir.join("credentials").to_string_lossy().to_string())).is_err());
+    }
+}
@@ -0,0 +1,166 @@
+use aws_sdk_s3::primitives::ByteStream;
+use aws_smithy_types::body::SdkBody;
+use bytes::Bytes;
+use http_body::{Body, Frame, SizeHint};
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+pub type BandwidthState = Arc<BandwidthLimiter>;
+
+// One budget shared by every upload and download. Tokens are bytes; the bucket holds
+// at most one second's worth, so an idle link can't bank a long burst.
+struct TokenBucket {
+    bytes_per_sec: u64,
+    tokens: f64,
+    updated: Instant,
+}
+
+impl TokenBucket {
+    fn new(bytes_per_sec: u64) -> Self {
+        Self {
+            bytes_per_sec,
+            tokens: bytes_per_sec as f64,
+            updated: Instant::now(),
+        }
+    }
+
+    // Takes `bytes` from the bucket and returns how long the caller must wait for them.
+    // The balance may go negative, which makes every later caller wait its turn too.
+    fn reserve(&mut self, bytes: u64) -> Duration {
+        let rate = self.bytes_per_sec as f64;
+        let now = Instant::now();
+        let refill = now.duration_since(self.updated).as_secs_f64() * rate;
+        self.tokens = (self.tokens + refill).min(rate) - bytes as f64;
+        self.updated = now;
+
+        if self.tokens >= 0.0 {
+            Duration::ZERO
+        } else {
+            Duration::from_secs_f64(-self.tokens / rate)
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct BandwidthLimiter {
+    bucket: Mutex<Option<TokenBucket>>,
+}
+
+impl BandwidthLimiter {
+    // Applied whenever general settings are loaded or saved; None or zero removes the cap
+    pub fn set_limit(&self, bytes_per_sec: Option<u64>) {
+        let bytes_per_sec = bytes_per_sec.filter(|&rate| rate > 0);
+        let mut bucket = self.bucket.lock().unwrap();
+        if bucket.as_ref().map(|bucket| bucket.bytes_per_sec) != bytes_per_sec {
+            debug!("Bandwidth limit set to {:?} bytes/s", bytes_per_sec);
+            *bucket = bytes_per_sec.map(TokenBucket::new);
+        }
+    }
+
+    fn reserve(&self, bytes: u64) -> Duration {
+        match self.bucket.lock().unwrap().as_mut() {
+            Some(bucket) => bucket.reserve(bytes),
+            None => Duration::ZERO,
+        }
+    }
+
+    // Waits until `bytes` more can be sent or received without exceeding the limit
+    pub async fn throttle(&self, bytes: u64) {
+        let wait = self.reserve(bytes);
+        if !wait.is_zero() {
+            tokio::time::sleep(wait).await;
+        }
+    }
+}
+
+// Paces an upload body chunk by chunk as the SDK sends it. Every retry of the request
+// rebuilds the body, so retries are paced too. Bodies read from a file can always be
+// rebuilt.
+pub fn throttle_body(body: ByteStream, limiter: &BandwidthState) -> ByteStream {
+    let inner = body.into_inner();
+    let limiter = Arc::clone(limiter);
+    ByteStream::new(SdkBody::retryable(move || {
+        let inner = inner.try_clone().unwrap_or_else(SdkBody::taken);
+        SdkBody::from_body_1_x(ThrottledBody { inner, limiter: Arc::clone(&limiter), pause: None })
+    }))
+}
+
+// Hands each chunk on right away and makes the next one wait for whatever the limiter
+// charged for it
+struct ThrottledBody {
+    inner: SdkBody,
+    limiter: BandwidthState,
+    pause: Option<Pin<Box<tokio::time::Sleep>>>,
+}
+
+impl Body for ThrottledBody {
+    type Data = Bytes;
+    type Error = aws_smithy_types::body::Error;
+
+    fn poll_frame(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Result<Frame<Bytes>, Self::Error>>> {
+        if let Some(pause) = self.pause.as_mut() {
+            ready!(pause.as_mut().poll(cx));
+            self.pause = None;
+        }
+
+        let frame = ready!(Pin::new(&mut self.inner).poll_frame(cx));
+        if let Some(data) = frame.as_ref().and_then(|frame| frame.as_ref().ok()).and_then(Frame::data_ref) {
+            let wait = self.limiter.reserve(data.len() as u64);
+            if !wait.is_zero() {
+                self.pause = Some(Box::pin(tokio::time::sleep(wait)));
+            }
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        Body::size_hint(&self.inner)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn throttled_upload_body_is_paced_and_keeps_its_length() {
+        let path = std::env::temp_dir().join(format!("bandwidth-{}.bin", uuid::Uuid::new_v4()));
+        let content = vec![7u8; 20_000];
+        tokio::fs::write(&path, &content).await.unwrap();
+
+        let limiter = BandwidthState::default();
+        limiter.set_limit(Some(10_000));
+        let body = ByteStream::read_from().path(&path).buffer_size(1_000).build().await.unwrap();
+        let body = throttle_body(body, &limiter);
+        assert_eq!(body.size_hint().1, Some(20_000));
+
+        // The first second's worth goes through at once, the other 10 KB take a second
+        let started = Instant::now();
+        let received = body.collect().await.unwrap().into_bytes();
+        let elapsed = started.elapsed();
+        tokio::fs::remove_file(&path).await.unwrap();
+
+        assert_eq!(received.as_ref(), content.as_slice());
+        assert!(elapsed >= Duration::from_millis(900), "took {:?}", elapsed);
+        assert!(elapsed < Duration::from_secs(3), "took {:?}", elapsed);
+    }
+
+    #[tokio::test]
+    async fn unlimited_bandwidth_never_waits() {
+        let limiter = BandwidthLimiter::default();
+        limiter.set_limit(Some(0));
+
+        let started = Instant::now();
+        limiter.throttle(100_000_000).await;
+
+        assert!(started.elapsed() < Duration::from_millis(100));
+    }
+}
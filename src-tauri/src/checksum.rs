@@ -0,0 +1,73 @@
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum ChecksumAlgorithm {
+    #[default]
+    Crc32c,
+    Sha256,
+}
+
+impl ChecksumAlgorithm {
+    pub fn to_sdk(self) -> aws_sdk_s3::types::ChecksumAlgorithm {
+        match self {
+            ChecksumAlgorithm::Crc32c => aws_sdk_s3::types::ChecksumAlgorithm::Crc32C,
+            ChecksumAlgorithm::Sha256 => aws_sdk_s3::types::ChecksumAlgorithm::Sha256,
+        }
+    }
+}
+
+// A checksum as S3 stores it: the base64 of the big-endian digest. Multipart uploads
+// get a checksum of the part checksums instead, with a "-<parts>" suffix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectChecksum {
+    pub algorithm: ChecksumAlgorithm,
+    pub value: String,
+}
+
+impl ObjectChecksum {
+    pub fn from_parts(crc32c: Option<&str>, sha256: Option<&str>) -> Option<Self> {
+        match (crc32c, sha256) {
+            (_, Some(value)) => Some(Self { algorithm: ChecksumAlgorithm::Sha256, value: value.to_string() }),
+            (Some(value), None) => Some(Self { algorithm: ChecksumAlgorithm::Crc32c, value: value.to_string() }),
+            (None, None) => None,
+        }
+    }
+
+    // Only checksums of the whole object can be compared with the downloaded bytes
+    pub fn is_full_object(&self) -> bool {
+        !self.value.contains('-')
+    }
+}
+
+// Computes a checksum incrementally while a body is streamed
+pub enum ChecksumHasher {
+    Crc32c(u32),
+    Sha256(Sha256),
+}
+
+impl ChecksumHasher {
+    pub fn new(algorithm: ChecksumAlgorithm) -> Self {
+        match algorithm {
+            ChecksumAlgorithm::Crc32c => ChecksumHasher::Crc32c(0),
+            ChecksumAlgorithm::Sha256 => ChecksumHasher::Sha256(Sha256::new()),
+        }
+    }
+
+    pub fn update(&mut self, data: &[u8]) {
+        match self {
+            ChecksumHasher::Crc32c(crc) => *crc = crc32c::crc32c_append(*crc, data),
+            ChecksumHasher::Sha256(hasher) => hasher.update(data),
+        }
+    }
+
+    pub fn finish(self) -> String {
+        let digest = match self {
+            ChecksumHasher::Crc32c(crc) => crc.to_be_bytes().to_vec(),
+            ChecksumHasher::Sha256(hasher) => hasher.finalize().to_vec(),
+        };
+        base64::engine::general_purpose::STANDARD.encode(digest)
+    }
+}
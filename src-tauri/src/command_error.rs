@@ -0,0 +1,111 @@
+use crate::s3_service::S3Error;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+// Mirrors S3Error so the frontend can branch on the kind of failure without parsing messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    InvalidCredentials,
+    BucketNotFound,
+    ObjectNotFound,
+    PermissionDenied,
+    ConnectionNotFound,
+    NetworkError,
+    ConfigurationError,
+    IoError,
+    // A download would replace a local file it wasn't allowed to
+    DestinationExists,
+    Cancelled,
+    MoveIncomplete,
+    ChecksumMismatch,
+    ObjectChanged,
+    PreconditionFailed,
+    ClockSkew,
+    // An encrypted settings import was attempted without a password
+    PasswordRequired,
+    Unknown,
+}
+
+// The error every command returns. `message` is meant for humans; the UI should decide
+// on retries and wording from `code` and `retryable`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CommandError {
+    pub code: ErrorCode,
+    pub message: String,
+    pub retryable: bool,
+}
+
+impl CommandError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            retryable: matches!(code, ErrorCode::NetworkError | ErrorCode::ObjectChanged),
+        }
+    }
+
+    // Prefixes the message ("Failed to list objects: ...") and keeps the code of `err`
+    pub fn with_context(context: &str, err: impl Into<CommandError>) -> Self {
+        let err = err.into();
+        Self {
+            message: format!("{}: {}", context, err.message),
+            ..err
+        }
+    }
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<S3Error> for CommandError {
+    fn from(err: S3Error) -> Self {
+        let code = match &err {
+            S3Error::InvalidCredentials => ErrorCode::InvalidCredentials,
+            S3Error::BucketNotFound => ErrorCode::BucketNotFound,
+            S3Error::ObjectNotFound => ErrorCode::ObjectNotFound,
+            S3Error::PermissionDenied | S3Error::RequesterPaysRequired => ErrorCode::PermissionDenied,
+            S3Error::ConnectionNotFound(_) => ErrorCode::ConnectionNotFound,
+            S3Error::NetworkError(_) => ErrorCode::NetworkError,
+            S3Error::ConfigurationError(_) => ErrorCode::ConfigurationError,
+            S3Error::IoError(_) => ErrorCode::IoError,
+            S3Error::DestinationExists(_) => ErrorCode::DestinationExists,
+            S3Error::Cancelled => ErrorCode::Cancelled,
+            S3Error::MoveIncomplete(_) => ErrorCode::MoveIncomplete,
+            S3Error::ChecksumMismatch(_) => ErrorCode::ChecksumMismatch,
+            S3Error::ObjectChanged => ErrorCode::ObjectChanged,
+            S3Error::PreconditionFailed => ErrorCode::PreconditionFailed,
+            S3Error::ClockSkew(_) => ErrorCode::ClockSkew,
+            S3Error::UnknownError(_) => ErrorCode::Unknown,
+        };
+        Self::new(code, err.to_string())
+    }
+}
+
+// Validation and settings failures that have no more specific code
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self::new(ErrorCode::Unknown, message)
+    }
+}
+
+impl From<&str> for CommandError {
+    fn from(message: &str) -> Self {
+        Self::new(ErrorCode::Unknown, message)
+    }
+}
+
+// Settings code returns boxed errors; a CommandError inside keeps its code
+impl From<Box<dyn std::error::Error>> for CommandError {
+    fn from(err: Box<dyn std::error::Error>) -> Self {
+        match err.downcast::<CommandError>() {
+            Ok(err) => *err,
+            Err(err) => Self::new(ErrorCode::Unknown, err.to_string()),
+        }
+    }
+}
@@ -1,4 +1,8 @@
-use crate::settings::{SettingsManager, AppSettings, GeneralSettings, AppearanceSettings, LayoutSettings, PermissionsSettings, ConnectionConfig};
+use crate::auto_refresh::{self, ActiveView};
+use crate::command_error::CommandError;
+use crate::prefix_watch::PrefixWatchState;
+use crate::s3_commands::{connect_in_background, disconnect_connection, S3ConnectionState};
+use crate::settings::{SettingsManager, ConnectionStats, AppSettings, GeneralSettings, AppearanceSettings, LayoutSettings, PermissionsSettings, ConnectionConfig};
 use std::path::PathBuf;
 use tokio::sync::Mutex;
 use tauri::{AppHandle, State};
@@ -9,14 +13,21 @@ pub type SettingsState = Mutex<Option<SettingsManager>>;
 pub async fn init_settings(
     app_handle: AppHandle,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_manager = SettingsManager::new(&app_handle)
-        .map_err(|e| format!("Failed to initialize settings manager: {}", e))?;
+        .map_err(|e| CommandError::with_context("Failed to initialize settings manager", e))?;
     
     let settings = settings_manager.load_settings().await
-        .map_err(|e| format!("Failed to load settings: {}", e))?;
+        .map_err(|e| CommandError::with_context("Failed to load settings", e))?;
+    let auto_connect = match settings_manager.default_connection() {
+        Some(connection) if settings.general.auto_connect_default => Some(connection.clone()),
+        _ => None,
+    };
     
     *settings_state.lock().await = Some(settings_manager);
+    if let Some(connection) = auto_connect {
+        connect_in_background(app_handle, connection);
+    }
     
     Ok(settings)
 }
@@ -24,11 +35,11 @@ pub async fn init_settings(
 #[tauri::command]
 pub async fn get_settings(
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let settings_guard = settings_state.lock().await;
     match settings_guard.as_ref() {
         Some(manager) => Ok(manager.get_current_settings()),
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
@@ -36,14 +47,14 @@ pub async fn get_settings(
 pub async fn save_settings(
     settings: AppSettings,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             manager.update_settings(settings).await
-                .map_err(|e| format!("Failed to save settings: {}", e))
+                .map_err(|e| CommandError::with_context("Failed to save settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
@@ -51,14 +62,14 @@ pub async fn save_settings(
 pub async fn update_general_settings(
     general: GeneralSettings,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             manager.update_general_settings(general).await
-                .map_err(|e| format!("Failed to update general settings: {}", e))
+                .map_err(|e| CommandError::with_context("Failed to update general settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
@@ -66,14 +77,14 @@ pub async fn update_general_settings(
 pub async fn update_appearance_settings(
     appearance: AppearanceSettings,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             manager.update_appearance_settings(appearance).await
-                .map_err(|e| format!("Failed to update appearance settings: {}", e))
+                .map_err(|e| CommandError::with_context("Failed to update appearance settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
@@ -81,14 +92,14 @@ pub async fn update_appearance_settings(
 pub async fn update_layout_settings(
     layout: LayoutSettings,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             manager.update_layout_settings(layout).await
-                .map_err(|e| format!("Failed to update layout settings: {}", e))
+                .map_err(|e| CommandError::with_context("Failed to update layout settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
@@ -96,14 +107,14 @@ pub async fn update_layout_settings(
 pub async fn update_permissions_settings(
     permissions: PermissionsSettings,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             manager.update_permissions_settings(permissions).await
-                .map_err(|e| format!("Failed to update permissions settings: {}", e))
+                .map_err(|e| CommandError::with_context("Failed to update permissions settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
@@ -111,14 +122,14 @@ pub async fn update_permissions_settings(
 pub async fn add_connection(
     connection: ConnectionConfig,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             manager.add_connection(connection).await
-                .map_err(|e| format!("Failed to add connection: {}", e))
+                .map_err(|e| CommandError::with_context("Failed to add connection", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
@@ -127,14 +138,83 @@ pub async fn update_connection(
     index: usize,
     connection: ConnectionConfig,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+    watches: State<'_, PrefixWatchState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<AppSettings, CommandError> {
+    let mut settings_guard = settings_state.lock().await;
+    match settings_guard.as_mut() {
+        Some(manager) => {
+            // The next command reconnects with the edited endpoint and credentials
+            let old_name = manager.get_current_settings().connections.get(index).map(|conn| conn.name.clone());
+            let settings = manager.update_connection(index, connection).await
+                .map_err(|e| CommandError::with_context("Failed to update connection", e))?;
+            if let Some(name) = old_name {
+                disconnect_connection(&s3_state, &watches, &name).await;
+            }
+            Ok(settings)
+        }
+        None => Err("Settings manager not initialized".into()),
+    }
+}
+
+#[tauri::command]
+pub async fn duplicate_connection(
+    index: usize,
+    settings_state: State<'_, SettingsState>,
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
-            manager.update_connection(index, connection).await
-                .map_err(|e| format!("Failed to update connection: {}", e))
+            manager.duplicate_connection(index).await
+                .map_err(|e| CommandError::with_context("Failed to duplicate connection", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
+    }
+}
+
+#[tauri::command]
+pub async fn reorder_connections(
+    from_index: usize,
+    to_index: usize,
+    settings_state: State<'_, SettingsState>,
+) -> Result<AppSettings, CommandError> {
+    let mut settings_guard = settings_state.lock().await;
+    match settings_guard.as_mut() {
+        Some(manager) => {
+            manager.reorder_connections(from_index, to_index).await
+                .map_err(|e| CommandError::with_context("Failed to reorder connections", e))
+        }
+        None => Err("Settings manager not initialized".into()),
+    }
+}
+
+#[tauri::command]
+pub async fn move_connection_to_top(
+    index: usize,
+    settings_state: State<'_, SettingsState>,
+) -> Result<AppSettings, CommandError> {
+    let mut settings_guard = settings_state.lock().await;
+    match settings_guard.as_mut() {
+        Some(manager) => {
+            manager.move_connection_to_top(index).await
+                .map_err(|e| CommandError::with_context("Failed to move connection", e))
+        }
+        None => Err("Settings manager not initialized".into()),
+    }
+}
+
+#[tauri::command]
+pub async fn get_connection_stats(
+    index: usize,
+    settings_state: State<'_, SettingsState>,
+) -> Result<ConnectionStats, CommandError> {
+    let settings_guard = settings_state.lock().await;
+    match settings_guard.as_ref() {
+        Some(manager) => manager
+            .connection_stats(index)
+            .cloned()
+            .ok_or_else(|| "Connection index out of bounds".into()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
@@ -142,73 +222,177 @@ pub async fn update_connection(
 pub async fn remove_connection(
     index: usize,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+    watches: State<'_, PrefixWatchState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
-            manager.remove_connection(index).await
-                .map_err(|e| format!("Failed to remove connection: {}", e))
+            let removed_name = manager.get_current_settings().connections.get(index).map(|conn| conn.name.clone());
+            let settings = manager.remove_connection(index).await
+                .map_err(|e| CommandError::with_context("Failed to remove connection", e))?;
+            if let Some(name) = removed_name {
+                disconnect_connection(&s3_state, &watches, &name).await;
+            }
+            Ok(settings)
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
+    }
+}
+
+#[tauri::command]
+pub async fn add_favorite(
+    connection_name: String,
+    path: String,
+    settings_state: State<'_, SettingsState>,
+) -> Result<AppSettings, CommandError> {
+    let mut settings_guard = settings_state.lock().await;
+    match settings_guard.as_mut() {
+        Some(manager) => {
+            manager.add_favorite(&connection_name, path).await
+                .map_err(|e| CommandError::with_context("Failed to add favorite", e))
+        }
+        None => Err("Settings manager not initialized".into()),
+    }
+}
+
+#[tauri::command]
+pub async fn remove_favorite(
+    connection_name: String,
+    path: String,
+    settings_state: State<'_, SettingsState>,
+) -> Result<AppSettings, CommandError> {
+    let mut settings_guard = settings_state.lock().await;
+    match settings_guard.as_mut() {
+        Some(manager) => {
+            manager.remove_favorite(&connection_name, &path).await
+                .map_err(|e| CommandError::with_context("Failed to remove favorite", e))
+        }
+        None => Err("Settings manager not initialized".into()),
+    }
+}
+
+#[tauri::command]
+pub async fn record_recent_path(
+    connection_name: String,
+    bucket: String,
+    prefix: String,
+    settings_state: State<'_, SettingsState>,
+) -> Result<AppSettings, CommandError> {
+    let mut settings_guard = settings_state.lock().await;
+    match settings_guard.as_mut() {
+        Some(manager) => {
+            manager.record_recent_path(&connection_name, bucket, prefix).await
+                .map_err(|e| CommandError::with_context("Failed to record recent path", e))
+        }
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
 #[tauri::command]
 pub async fn export_settings(
     export_path: String,
+    password: Option<String>,
     settings_state: State<'_, SettingsState>,
-) -> Result<(), String> {
+) -> Result<(), CommandError> {
     let settings_guard = settings_state.lock().await;
     match settings_guard.as_ref() {
         Some(manager) => {
             let path = PathBuf::from(export_path);
-            manager.export_settings(path).await
-                .map_err(|e| format!("Failed to export settings: {}", e))
+            manager.export_settings(path, password.as_deref().filter(|p| !p.is_empty())).await
+                .map_err(|e| CommandError::with_context("Failed to export settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
 #[tauri::command]
 pub async fn import_settings(
     import_path: String,
+    password: Option<String>,
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             let path = PathBuf::from(import_path);
-            manager.import_settings(path).await
-                .map_err(|e| format!("Failed to import settings: {}", e))
+            manager.import_settings(path, password.as_deref()).await
+                .map_err(|e| CommandError::with_context("Failed to import settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
 #[tauri::command]
 pub async fn reset_settings(
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             manager.reset_to_defaults().await
-                .map_err(|e| format!("Failed to reset settings: {}", e))
+                .map_err(|e| CommandError::with_context("Failed to reset settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
 }
 
 #[tauri::command]
 pub async fn reload_settings(
     settings_state: State<'_, SettingsState>,
-) -> Result<AppSettings, String> {
+) -> Result<AppSettings, CommandError> {
     let mut settings_guard = settings_state.lock().await;
     match settings_guard.as_mut() {
         Some(manager) => {
             manager.load_settings().await
-                .map_err(|e| format!("Failed to reload settings: {}", e))
+                .map_err(|e| CommandError::with_context("Failed to reload settings", e))
         }
-        None => Err("Settings manager not initialized".to_string()),
+        None => Err("Settings manager not initialized".into()),
     }
-}
\ No newline at end of file
+}
+
+// Reading ~/.aws is blocking file I/O, so it runs on the blocking pool
+#[tauri::command]
+pub async fn import_aws_profiles(
+    path: Option<String>,
+) -> Result<Vec<ConnectionConfig>, CommandError> {
+    tokio::task::spawn_blocking(move || crate::aws_profiles::import_aws_profiles(path.filter(|p| !p.is_empty())))
+        .await
+        .map_err(|e| format!("Profile import task failed: {}", e))?
+        .map_err(|e| CommandError::with_context("Failed to import AWS profiles", e))
+}
+
+#[tauri::command]
+pub async fn get_log_file_path() -> Result<String, CommandError> {
+    crate::logging::log_file_path()
+        .map(|path| path.to_string_lossy().to_string())
+        .ok_or_else(|| "Logging to a file is not enabled".into())
+}
+
+// Sets the location refresh ticks are emitted for; no bucket means nothing to refresh
+#[tauri::command]
+pub async fn set_active_view(
+    connection_name: String,
+    bucket: Option<String>,
+    prefix: Option<String>,
+) -> Result<(), CommandError> {
+    let view = bucket.map(|bucket| ActiveView {
+        connection_name,
+        bucket,
+        prefix: prefix.unwrap_or_default(),
+    });
+    auto_refresh::set_active_view(view);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn pause_auto_refresh() -> Result<(), CommandError> {
+    auto_refresh::set_paused(true);
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn resume_auto_refresh() -> Result<(), CommandError> {
+    auto_refresh::set_paused(false);
+    Ok(())
+}
@@ -0,0 +1,130 @@
+use serde::Serialize;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Mutex, OnceLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tauri::AppHandle;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+pub const CONNECTION_LOST_EVENT: &str = "connection-lost";
+pub const CONNECTION_RESTORED_EVENT: &str = "connection-restored";
+// This many transport failures within the window mark a connection as lost
+const LOST_AFTER_FAILURES: usize = 3;
+const FAILURE_WINDOW: Duration = Duration::from_secs(30);
+pub const MAX_RECONNECT_ATTEMPTS: u32 = 5;
+const INITIAL_RECONNECT_DELAY: Duration = Duration::from_secs(2);
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+// Payload of the `connection-lost` and `connection-restored` events. `attempt` is the
+// reconnect attempt that succeeded, or None for a manual reconnect.
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionHealthEvent {
+    pub connection_name: String,
+    pub attempt: Option<u32>,
+}
+
+// Transport failures of one connection's service. Only services cached under a
+// connection name report anything; throwaway ones built for a test never do.
+#[derive(Default)]
+pub struct TransportHealth {
+    connection_name: OnceLock<String>,
+    failures: Mutex<VecDeque<Instant>>,
+    lost: AtomicBool,
+}
+
+impl TransportHealth {
+    pub fn attach(&self, connection_name: &str) {
+        let _ = self.connection_name.set(connection_name.to_string());
+    }
+
+    // Reports the connection as lost once, when enough failures pile up in the window.
+    // A reconnect replaces the service; if every attempt fails, it stays lost until
+    // reconnect_s3 is called or a request is answered again.
+    pub fn record_failure(&self) {
+        let Some(name) = self.connection_name.get() else {
+            return;
+        };
+        let now = Instant::now();
+        let mut failures = self.failures.lock().unwrap();
+        failures.push_back(now);
+        while failures.front().is_some_and(|at| now.duration_since(*at) > FAILURE_WINDOW) {
+            failures.pop_front();
+        }
+        if failures.len() < LOST_AFTER_FAILURES || self.lost.swap(true, Ordering::SeqCst) {
+            return;
+        }
+
+        if let Some(sender) = LOST.get() {
+            warn!("Connection '{}' failed {} times in a row, reconnecting", name, failures.len());
+            let _ = sender.send(name.clone());
+        }
+    }
+
+    // The server answered, even if with an error, so the network is fine. A lost
+    // connection that recovered on its own can be reported lost again.
+    pub fn record_answer(&self) {
+        self.failures.lock().unwrap().clear();
+        if self.lost.swap(false, Ordering::SeqCst) {
+            if let Some(name) = self.connection_name.get() {
+                info!("Connection '{}' is answering again", name);
+            }
+        }
+    }
+}
+
+static LOST: OnceLock<mpsc::UnboundedSender<String>> = OnceLock::new();
+
+// Starts the task that reconnects lost connections, one reconnect loop per connection
+pub fn spawn(app: AppHandle) {
+    let (sender, mut lost) = mpsc::unbounded_channel::<String>();
+    if LOST.set(sender).is_err() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        let reconnecting = std::sync::Arc::new(Mutex::new(HashSet::new()));
+        while let Some(connection_name) = lost.recv().await {
+            if !reconnecting.lock().unwrap().insert(connection_name.clone()) {
+                continue;
+            }
+            let (app, reconnecting) = (app.clone(), reconnecting.clone());
+            tauri::async_runtime::spawn(async move {
+                crate::s3_commands::reconnect_with_backoff(&app, &connection_name).await;
+                reconnecting.lock().unwrap().remove(&connection_name);
+            });
+        }
+    });
+}
+
+// 2s, 4s, 8s, ... capped at MAX_RECONNECT_DELAY
+pub fn reconnect_delay(attempt: u32) -> Duration {
+    INITIAL_RECONNECT_DELAY
+        .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+        .min(MAX_RECONNECT_DELAY)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn an_answer_clears_a_lost_connection() {
+        let health = TransportHealth::default();
+        health.attach("test");
+        for _ in 0..LOST_AFTER_FAILURES {
+            health.record_failure();
+        }
+        assert!(health.lost.load(Ordering::SeqCst));
+
+        health.record_answer();
+        assert!(!health.lost.load(Ordering::SeqCst));
+
+        for _ in 0..LOST_AFTER_FAILURES - 1 {
+            health.record_failure();
+        }
+        assert!(!health.lost.load(Ordering::SeqCst));
+        health.record_failure();
+        assert!(health.lost.load(Ordering::SeqCst));
+    }
+}
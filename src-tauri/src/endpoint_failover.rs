@@ -0,0 +1,187 @@
+use aws_sdk_s3::config::interceptors::{BeforeTransmitInterceptorContextMut, FinalizerInterceptorContextRef};
+use aws_sdk_s3::config::{ConfigBag, Intercept, RuntimeComponents};
+use aws_sdk_s3::error::BoxError;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+// Failures within this long of a failover are taken to be the same outage
+const FAILOVER_COOLDOWN: Duration = Duration::from_secs(5);
+
+// The endpoints of one connection, the configured one first, and which of them is in use
+#[derive(Debug)]
+pub struct EndpointFailover {
+    endpoints: Vec<String>,
+    active: AtomicUsize,
+    last_failover: Mutex<Option<Instant>>,
+}
+
+impl EndpointFailover {
+    pub fn new(endpoints: Vec<String>) -> Self {
+        Self {
+            endpoints,
+            active: AtomicUsize::new(0),
+            last_failover: Mutex::new(None),
+        }
+    }
+
+    pub fn endpoint_count(&self) -> usize {
+        self.endpoints.len()
+    }
+
+    pub fn active_index(&self) -> usize {
+        self.active.load(Ordering::SeqCst)
+    }
+
+    pub fn active(&self) -> &str {
+        &self.endpoints[self.active_index()]
+    }
+
+    pub fn endpoint(&self, index: usize) -> &str {
+        &self.endpoints[index]
+    }
+
+    pub fn activate(&self, index: usize) {
+        self.active.store(index, Ordering::SeqCst);
+    }
+
+    // Moves the following requests, including the SDK's retry of the one that failed, to
+    // the next endpoint. A burst of failures within the cooldown only moves one endpoint on.
+    pub fn fail_over(&self) {
+        if self.endpoints.len() < 2 {
+            return;
+        }
+        let mut last_failover = self.last_failover.lock().unwrap();
+        if last_failover.is_some_and(|at| at.elapsed() < FAILOVER_COOLDOWN) {
+            return;
+        }
+        *last_failover = Some(Instant::now());
+
+        let from = self.active_index();
+        let to = (from + 1) % self.endpoints.len();
+        self.activate(to);
+        warn!("Endpoint {} is unreachable, switching to {}", self.endpoints[from], self.endpoints[to]);
+    }
+}
+
+// Clients are built for the configured endpoint. Every attempt is moved to the active
+// endpoint just before it is signed, and an attempt that can't reach its endpoint fails
+// over, so the retry goes to the next one. Region clients share the interceptor and
+// fail over with the rest.
+#[derive(Debug)]
+pub struct FailoverInterceptor(pub Arc<EndpointFailover>);
+
+impl Intercept for FailoverInterceptor {
+    fn name(&self) -> &'static str {
+        "EndpointFailover"
+    }
+
+    fn modify_before_signing(
+        &self,
+        context: &mut BeforeTransmitInterceptorContextMut<'_>,
+        _runtime_components: &RuntimeComponents,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        let active = self.0.active_index();
+        if active == 0 {
+            return Ok(());
+        }
+        let request = context.request_mut();
+        if let Some(uri) = rebase_uri(request.uri(), &self.0.endpoints[0], &self.0.endpoints[active]) {
+            request.set_uri(uri)?;
+        }
+        Ok(())
+    }
+
+    fn read_after_attempt(
+        &self,
+        context: &FinalizerInterceptorContextRef<'_>,
+        _runtime_components: &RuntimeComponents,
+        _cfg: &mut ConfigBag,
+    ) -> Result<(), BoxError> {
+        if let Some(Err(err)) = context.output_or_error() {
+            let unreachable = err.is_timeout_error() || err.as_connector_error().is_some_and(|err| !err.is_user());
+            if unreachable {
+                self.0.fail_over();
+            }
+        }
+        Ok(())
+    }
+}
+
+// Moves a request URI from one endpoint to another, keeping the bucket subdomain of
+// virtual-hosted requests and the path below the endpoint's own. The rest of the URI is
+// copied as is so nothing gets encoded twice. None if the URI isn't under `from`.
+fn rebase_uri(uri: &str, from: &str, to: &str) -> Option<String> {
+    let (from, to) = (url::Url::parse(from).ok()?, url::Url::parse(to).ok()?);
+    let (scheme, rest) = uri.split_once("://")?;
+    let authority_end = rest.find(['/', '?']).unwrap_or(rest.len());
+    let (authority, path) = rest.split_at(authority_end);
+
+    let port = |url: &url::Url| url.port().map(|port| format!(":{}", port)).unwrap_or_default();
+    let from_authority = format!("{}{}", from.host_str()?, port(&from));
+    let subdomain = match authority.strip_suffix(from_authority.as_str())? {
+        "" => "",
+        subdomain if subdomain.ends_with('.') => subdomain,
+        _ => return None,
+    };
+    if scheme != from.scheme() {
+        return None;
+    }
+    let path = path.strip_prefix(from.path().trim_end_matches('/'))?;
+
+    Some(format!(
+        "{}://{}{}{}{}{}",
+        to.scheme(),
+        subdomain,
+        to.host_str()?,
+        port(&to),
+        to.path().trim_end_matches('/'),
+        path
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rebases_request_uris_onto_the_fallback() {
+        let (from, to) = ("http://minio-a:9000", "https://minio-b.example.com");
+        assert_eq!(
+            rebase_uri("http://minio-a:9000/bucket/dir/a%20b.txt?x-id=GetObject", from, to).as_deref(),
+            Some("https://minio-b.example.com/bucket/dir/a%20b.txt?x-id=GetObject")
+        );
+        assert_eq!(
+            rebase_uri("http://bucket.minio-a:9000/key", from, to).as_deref(),
+            Some("https://bucket.minio-b.example.com/key")
+        );
+        assert_eq!(rebase_uri("http://minio-a:9000", from, to).as_deref(), Some("https://minio-b.example.com"));
+    }
+
+    #[test]
+    fn rebases_endpoints_with_a_base_path() {
+        assert_eq!(
+            rebase_uri("https://a.example.com/s3/bucket/key", "https://a.example.com/s3", "https://b.example.com/storage/")
+                .as_deref(),
+            Some("https://b.example.com/storage/bucket/key")
+        );
+    }
+
+    #[test]
+    fn leaves_other_hosts_alone() {
+        let (from, to) = ("http://minio-a:9000", "http://minio-b:9000");
+        assert_eq!(rebase_uri("http://other-minio-a:9000/bucket", from, to), None);
+        assert_eq!(rebase_uri("http://minio-a:9001/bucket", from, to), None);
+        assert_eq!(rebase_uri("https://minio-a:9000/bucket", from, to), None);
+    }
+
+    #[test]
+    fn failover_moves_one_endpoint_per_outage() {
+        let failover = EndpointFailover::new(vec!["http://a".to_string(), "http://b".to_string(), "http://c".to_string()]);
+        failover.fail_over();
+        failover.fail_over();
+        assert_eq!(failover.active(), "http://b");
+    }
+}
@@ -0,0 +1,127 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// Whether sizes are shown in powers of 1024 (KiB, MiB) or 1000 (KB, MB)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SizeUnits {
+    #[default]
+    Binary,
+    Decimal,
+}
+
+const BINARY_UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+const DECIMAL_UNITS: &[&str] = &["B", "KB", "MB", "GB", "TB", "PB", "EB"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FormattedObjectInfo {
+    pub size_human: Option<String>,
+    pub modified_relative: Option<String>,
+}
+
+// e.g. "1.2 MiB"; whole bytes are shown without a fraction
+pub fn format_size(bytes: u64, units: SizeUnits) -> String {
+    let (base, names) = match units {
+        SizeUnits::Binary => (1024.0, BINARY_UNITS),
+        SizeUnits::Decimal => (1000.0, DECIMAL_UNITS),
+    };
+
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= base && unit < names.len() - 1 {
+        value /= base;
+        unit += 1;
+    }
+    // 1023.96 KiB would round to "1024.0 KiB"; show the next unit instead
+    if unit > 0 && unit < names.len() - 1 && (value * 10.0).round() / 10.0 >= base {
+        value /= base;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, names[0])
+    } else {
+        format!("{:.1} {}", value, names[unit])
+    }
+}
+
+// e.g. "just now", "5 minutes ago", "3 days ago"; times ahead of `now` read "in ..."
+pub fn format_relative(time: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let seconds = now.signed_duration_since(time).num_seconds();
+    if seconds.abs() < 45 {
+        return "just now".to_string();
+    }
+
+    let (count, unit) = match seconds.abs() {
+        s if s < 3600 => ((s / 60).max(1), "minute"),
+        s if s < 86_400 => (s / 3600, "hour"),
+        s if s < 30 * 86_400 => (s / 86_400, "day"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+    let plural = if count == 1 { "" } else { "s" };
+
+    if seconds < 0 {
+        format!("in {} {}{}", count, unit, plural)
+    } else {
+        format!("{} {}{} ago", count, unit, plural)
+    }
+}
+
+pub fn format_object_info(size: Option<i64>, last_modified: Option<&str>, units: SizeUnits) -> FormattedObjectInfo {
+    let now = Utc::now();
+    FormattedObjectInfo {
+        size_human: size.map(|size| format_size(size.max(0) as u64, units)),
+        modified_relative: last_modified
+            .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+            .map(|time| format_relative(time.with_timezone(&Utc), now)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn formats_sizes_at_unit_boundaries() {
+        assert_eq!(format_size(0, SizeUnits::Binary), "0 B");
+        assert_eq!(format_size(1023, SizeUnits::Binary), "1023 B");
+        assert_eq!(format_size(1024, SizeUnits::Binary), "1.0 KiB");
+        assert_eq!(format_size(1536, SizeUnits::Binary), "1.5 KiB");
+        assert_eq!(format_size(1_048_473, SizeUnits::Binary), "1023.9 KiB");
+        // 1023.96 KiB rounds to 1024.0, so it moves up a unit
+        assert_eq!(format_size(1_048_535, SizeUnits::Binary), "1.0 MiB");
+        assert_eq!(format_size(u64::MAX, SizeUnits::Binary), "16.0 EiB");
+
+        assert_eq!(format_size(999, SizeUnits::Decimal), "999 B");
+        assert_eq!(format_size(1000, SizeUnits::Decimal), "1.0 KB");
+        assert_eq!(format_size(999_950, SizeUnits::Decimal), "1.0 MB");
+    }
+
+    #[test]
+    fn formats_relative_times() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let ago = |seconds: i64| format_relative(now - Duration::seconds(seconds), now);
+
+        assert_eq!(ago(0), "just now");
+        assert_eq!(ago(44), "just now");
+        assert_eq!(ago(45), "1 minute ago");
+        assert_eq!(ago(5 * 60), "5 minutes ago");
+        assert_eq!(ago(90 * 60), "1 hour ago");
+        assert_eq!(ago(3 * 86_400), "3 days ago");
+        assert_eq!(ago(60 * 86_400), "2 months ago");
+        assert_eq!(ago(400 * 86_400), "1 year ago");
+    }
+
+    #[test]
+    fn formats_future_times() {
+        let now = DateTime::parse_from_rfc3339("2024-06-01T12:00:00Z").unwrap().with_timezone(&Utc);
+        let ahead = |seconds: i64| format_relative(now + Duration::seconds(seconds), now);
+
+        assert_eq!(ahead(44), "just now");
+        assert_eq!(ahead(45), "in 1 minute");
+        assert_eq!(ahead(2 * 3600), "in 2 hours");
+        assert_eq!(ahead(2 * 365 * 86_400), "in 2 years");
+    }
+}
@@ -0,0 +1,541 @@
+use aws_smithy_runtime_api::client::http::{
+    HttpClient, HttpConnector, HttpConnectorFuture, HttpConnectorSettings, SharedHttpClient, SharedHttpConnector,
+};
+use aws_smithy_runtime_api::client::orchestrator::{HttpRequest, HttpResponse};
+use aws_smithy_runtime_api::client::result::ConnectorError;
+use aws_smithy_runtime_api::client::runtime_components::RuntimeComponents;
+use aws_smithy_types::body::SdkBody;
+use base64::Engine;
+use hyper::header::HeaderValue;
+use hyper::Uri;
+use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder, MaybeHttpsStream};
+use hyper_util::client::legacy::connect::proxy::{SocksV5, Tunnel};
+use hyper::rt::ReadBufCursor;
+use hyper_util::client::legacy::connect::HttpConnector as TcpConnector;
+use hyper_util::client::legacy::connect::{Connected, Connection};
+use hyper_util::client::legacy::Client;
+use hyper_util::rt::{TokioExecutor, TokioIo};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+use tokio::net::TcpStream;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tower_service::Service;
+use tracing::{debug, error, info, warn};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProxyScheme {
+    Http,
+    Https,
+    Socks5,
+}
+
+#[derive(Debug, Clone)]
+pub struct ProxySettings {
+    pub scheme: ProxyScheme,
+    pub host: String,
+    pub port: u16,
+    pub credentials: Option<(String, String)>,
+}
+
+// Parses `http://`, `https://` and `socks5://` proxy URLs, with optional
+// `user:password@` credentials.
+pub fn parse_proxy_url(proxy_url: &str) -> Result<ProxySettings, String> {
+    let url = url::Url::parse(proxy_url).map_err(|e| format!("Invalid proxy URL '{}': {}", proxy_url, e))?;
+
+    let scheme = match url.scheme() {
+        "http" => ProxyScheme::Http,
+        "https" => ProxyScheme::Https,
+        "socks5" | "socks5h" => ProxyScheme::Socks5,
+        other => return Err(format!("Unsupported proxy scheme '{}', use http, https or socks5", other)),
+    };
+
+    let host = url
+        .host_str()
+        .ok_or_else(|| format!("Proxy URL '{}' has no host", proxy_url))?
+        .to_string();
+
+    let port = url.port().unwrap_or(match scheme {
+        ProxyScheme::Http => 80,
+        ProxyScheme::Https => 443,
+        ProxyScheme::Socks5 => 1080,
+    });
+
+    let credentials = if url.username().is_empty() {
+        None
+    } else {
+        let decode = |s: &str| {
+            percent_encoding::percent_decode_str(s)
+                .decode_utf8()
+                .map(|decoded| decoded.into_owned())
+                .map_err(|_| format!("Proxy credentials in '{}' are not valid UTF-8", proxy_url))
+        };
+        Some((decode(url.username())?, decode(url.password().unwrap_or_default())?))
+    };
+
+    Ok(ProxySettings {
+        scheme,
+        host,
+        port,
+        credentials,
+    })
+}
+
+// Accepts any server certificate. Only ever used when a connection has explicitly
+// opted in with `allow_invalid_certs` (e.g. MinIO/Ceph behind a self-signed cert).
+// Handshake signatures are still checked, so the peer must hold the certificate's key.
+#[derive(Debug)]
+struct NoCertificateVerification(Arc<CryptoProvider>);
+
+impl ServerCertVerifier for NoCertificateVerification {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(message, cert, dss, &self.0.signature_verification_algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.0.signature_verification_algorithms.supported_schemes()
+    }
+}
+
+fn tls_config(allow_invalid_certs: bool) -> Result<rustls::ClientConfig, String> {
+    let provider = Arc::new(rustls::crypto::aws_lc_rs::default_provider());
+    let builder = rustls::ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_safe_default_protocol_versions()
+        .map_err(|e| format!("Failed to set up TLS: {}", e))?;
+
+    if allow_invalid_certs {
+        warn!("TLS certificate verification is disabled for this connection");
+        return Ok(builder
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(NoCertificateVerification(provider)))
+            .with_no_client_auth());
+    }
+
+    let mut roots = rustls::RootCertStore::empty();
+    let native_certs = rustls_native_certs::load_native_certs();
+    for e in &native_certs.errors {
+        error!("Failed to load native root certificates: {}", e);
+    }
+    let (_, skipped) = roots.add_parsable_certificates(native_certs.certs);
+    if skipped > 0 {
+        warn!("Skipping {} invalid native root certificates", skipped);
+    }
+
+    Ok(builder.with_root_certificates(roots).with_no_client_auth())
+}
+
+// Builds a custom HTTP client when a connection needs something the SDK's default
+// client can't do. Returns None when the default client is fine. `max_connections`
+// caps the connections open to each host, idle or busy; a request that finds them all
+// busy waits until one is free.
+pub fn build_http_client(
+    allow_invalid_certs: bool,
+    proxy: Option<ProxySettings>,
+    max_connections: Option<u32>,
+) -> Result<Option<SharedHttpClient>, String> {
+    if !allow_invalid_certs && proxy.is_none() && max_connections.is_none() {
+        return Ok(None);
+    }
+
+    if let Some(proxy) = &proxy {
+        info!("Routing S3 traffic through proxy {}:{}", proxy.host, proxy.port);
+    }
+    if let Some(max_connections) = max_connections {
+        debug!("Keeping up to {} connections per host", max_connections);
+    }
+    Ok(Some(SharedHttpClient::new(CustomHttpClient {
+        tls: tls_config(allow_invalid_certs)?,
+        proxy,
+        connection_limit: max_connections.map(|max| Arc::new(ConnectionLimit::new(max as usize))),
+        connectors: Mutex::new(HashMap::new()),
+    })))
+}
+
+struct CustomHttpClient {
+    tls: rustls::ClientConfig,
+    proxy: Option<ProxySettings>,
+    // Shared by every connector, so the cap holds across connect timeouts
+    connection_limit: Option<Arc<ConnectionLimit>>,
+    // One connector per connect timeout, so all requests with the same settings share a pool
+    connectors: Mutex<HashMap<Option<Duration>, SharedHttpConnector>>,
+}
+
+// Proxy credentials stay out of the SDK's debug logging
+impl fmt::Debug for CustomHttpClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CustomHttpClient")
+            .field("proxy", &self.proxy.as_ref().map(|proxy| (proxy.scheme, &proxy.host, proxy.port)))
+            .field("max_connections", &self.connection_limit.as_ref().map(|limit| limit.max))
+            .finish_non_exhaustive()
+    }
+}
+
+impl HttpClient for CustomHttpClient {
+    fn http_connector(&self, settings: &HttpConnectorSettings, _components: &RuntimeComponents) -> SharedHttpConnector {
+        let connect_timeout = settings.connect_timeout();
+        self.connectors
+            .lock()
+            .unwrap()
+            .entry(connect_timeout)
+            .or_insert_with(|| SharedHttpConnector::new(self.hyper_connector(connect_timeout)))
+            .clone()
+    }
+}
+
+impl CustomHttpClient {
+    fn hyper_connector(&self, connect_timeout: Option<Duration>) -> HyperConnector {
+        let mut tcp = TcpConnector::new();
+        // hyper-rustls hands https:// URIs to the TCP connector as well
+        tcp.enforce_http(false);
+        tcp.set_connect_timeout(connect_timeout);
+
+        let connector = match &self.proxy {
+            None => ProxyConnector::Direct(tcp),
+            Some(proxy) => {
+                let proxy_uri = proxy_uri(proxy);
+                match proxy.scheme {
+                    ProxyScheme::Http => ProxyConnector::Http(with_basic_auth(Tunnel::new(proxy_uri, tcp), proxy)),
+                    ProxyScheme::Https => {
+                        let to_proxy = https_connector(self.tls.clone(), tcp);
+                        ProxyConnector::Https(with_basic_auth(Tunnel::new(proxy_uri, to_proxy), proxy))
+                    }
+                    ProxyScheme::Socks5 => {
+                        let socks = SocksV5::new(proxy_uri, tcp);
+                        ProxyConnector::Socks5(match &proxy.credentials {
+                            Some((user, password)) => socks.with_auth(user.clone(), password.clone()),
+                            None => socks,
+                        })
+                    }
+                }
+            }
+        };
+
+        let mut builder = Client::builder(TokioExecutor::new());
+        if let Some(limit) = &self.connection_limit {
+            builder.pool_max_idle_per_host(limit.max);
+        }
+        let connector = HostLimited {
+            inner: https_connector(self.tls.clone(), connector),
+            limit: self.connection_limit.clone(),
+        };
+        HyperConnector {
+            client: builder.build(connector),
+        }
+    }
+}
+
+fn https_connector<C>(tls: rustls::ClientConfig, connector: C) -> HttpsConnector<C> {
+    HttpsConnectorBuilder::new()
+        .with_tls_config(tls)
+        .https_or_http()
+        .enable_http1()
+        .wrap_connector(connector)
+}
+
+fn proxy_uri(proxy: &ProxySettings) -> Uri {
+    let scheme = match proxy.scheme {
+        ProxyScheme::Http => "http",
+        ProxyScheme::Https => "https",
+        ProxyScheme::Socks5 => "socks5",
+    };
+    // parse_proxy_url already checked the host, so this always parses
+    format!("{}://{}:{}", scheme, proxy.host, proxy.port)
+        .parse()
+        .unwrap_or_default()
+}
+
+fn with_basic_auth<C>(tunnel: Tunnel<C>, proxy: &ProxySettings) -> Tunnel<C> {
+    let Some((user, password)) = &proxy.credentials else {
+        return tunnel;
+    };
+    let token = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", user, password));
+    match HeaderValue::from_str(&format!("Basic {}", token)) {
+        Ok(auth) => tunnel.with_auth(auth),
+        Err(e) => {
+            warn!("Ignoring proxy credentials that can't be sent in a header: {}", e);
+            tunnel
+        }
+    }
+}
+
+// hyper's pool only limits idle connections, so the cap on open ones is kept here: every
+// connection holds one of its host's slots until it is closed
+#[derive(Debug)]
+struct ConnectionLimit {
+    max: usize,
+    hosts: Mutex<HashMap<String, Arc<Semaphore>>>,
+}
+
+impl ConnectionLimit {
+    fn new(max: usize) -> Self {
+        Self {
+            max,
+            hosts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn slots_for(&self, target: &Uri) -> Arc<Semaphore> {
+        let authority = target.authority().map_or("", |authority| authority.as_str());
+        let host = format!("{}://{}", target.scheme_str().unwrap_or_default(), authority);
+        let mut hosts = self.hosts.lock().unwrap();
+        Arc::clone(hosts.entry(host).or_insert_with(|| Arc::new(Semaphore::new(self.max))))
+    }
+}
+
+// Waits for a free slot on the target host before connecting
+#[derive(Clone)]
+struct HostLimited<C> {
+    inner: C,
+    limit: Option<Arc<ConnectionLimit>>,
+}
+
+impl<C> Service<Uri> for HostLimited<C>
+where
+    C: Service<Uri> + Clone + Send + 'static,
+    C::Response: Send,
+    C::Error: Into<BoxError>,
+    C::Future: Send,
+{
+    type Response = LimitedConnection<C::Response>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, target: Uri) -> Self::Future {
+        // The ready connector is the one to call, and a fresh clone takes its place
+        let fresh = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, fresh);
+        let slots = self.limit.as_ref().map(|limit| limit.slots_for(&target));
+        Box::pin(async move {
+            let slot = match slots {
+                Some(slots) => Some(slots.acquire_owned().await?),
+                None => None,
+            };
+            let io = inner.call(target).await.map_err(Into::into)?;
+            Ok(LimitedConnection { io, _slot: slot })
+        })
+    }
+}
+
+struct LimitedConnection<T> {
+    io: T,
+    _slot: Option<OwnedSemaphorePermit>,
+}
+
+impl<T: Connection> Connection for LimitedConnection<T> {
+    fn connected(&self) -> Connected {
+        self.io.connected()
+    }
+}
+
+impl<T: hyper::rt::Read + Unpin> hyper::rt::Read for LimitedConnection<T> {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: ReadBufCursor<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.io).poll_read(cx, buf)
+    }
+}
+
+impl<T: hyper::rt::Write + Unpin> hyper::rt::Write for LimitedConnection<T> {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.io).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.io).poll_shutdown(cx)
+    }
+
+    fn is_write_vectored(&self) -> bool {
+        self.io.is_write_vectored()
+    }
+
+    fn poll_write_vectored(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        bufs: &[std::io::IoSlice<'_>],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.io).poll_write_vectored(cx, bufs)
+    }
+}
+
+type ProxyStream = MaybeHttpsStream<TokioIo<TcpStream>>;
+
+// Reaches the target host directly, through an HTTP(S) CONNECT tunnel or through a
+// SOCKS5 proxy. TLS to the target itself is layered on top by hyper-rustls, and the
+// tunnelled stream looks like a direct connection to hyper, so requests keep their
+// origin-form URIs.
+#[derive(Clone)]
+enum ProxyConnector {
+    Direct(TcpConnector),
+    Http(Tunnel<TcpConnector>),
+    Https(Tunnel<HttpsConnector<TcpConnector>>),
+    Socks5(SocksV5<TcpConnector>),
+}
+
+impl Service<Uri> for ProxyConnector {
+    type Response = ProxyStream;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<ProxyStream, BoxError>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self {
+            ProxyConnector::Direct(c) => c.poll_ready(cx).map_err(Into::into),
+            ProxyConnector::Http(c) => c.poll_ready(cx).map_err(Into::into),
+            ProxyConnector::Https(c) => c.poll_ready(cx).map_err(Into::into),
+            ProxyConnector::Socks5(c) => c.poll_ready(cx).map_err(Into::into),
+        }
+    }
+
+    fn call(&mut self, target: Uri) -> Self::Future {
+        match self {
+            ProxyConnector::Direct(c) => {
+                let connecting = c.call(target);
+                Box::pin(async move { Ok(MaybeHttpsStream::Http(connecting.await?)) })
+            }
+            ProxyConnector::Http(c) => {
+                let connecting = c.call(target);
+                Box::pin(async move { Ok(MaybeHttpsStream::Http(connecting.await?)) })
+            }
+            ProxyConnector::Https(c) => {
+                let connecting = c.call(target);
+                Box::pin(async move { Ok(connecting.await?) })
+            }
+            ProxyConnector::Socks5(c) => {
+                let connecting = c.call(target);
+                Box::pin(async move { Ok(MaybeHttpsStream::Http(connecting.await?)) })
+            }
+        }
+    }
+}
+
+#[derive(Debug)]
+struct HyperConnector {
+    client: Client<HostLimited<HttpsConnector<ProxyConnector>>, SdkBody>,
+}
+
+impl HttpConnector for HyperConnector {
+    fn call(&self, request: HttpRequest) -> HttpConnectorFuture {
+        let request = match request.try_into_http1x() {
+            Ok(request) => request,
+            Err(e) => return HttpConnectorFuture::ready(Err(ConnectorError::user(e.into()))),
+        };
+        let response = self.client.request(request);
+        HttpConnectorFuture::new(async move {
+            let response = response.await.map_err(connector_error)?;
+            HttpResponse::try_from(response.map(SdkBody::from_body_1_x)).map_err(|e| ConnectorError::other(e.into(), None))
+        })
+    }
+}
+
+// Transport failures are reported as I/O errors so the SDK retries them and the
+// connection health tracking sees them
+fn connector_error(err: hyper_util::client::legacy::Error) -> ConnectorError {
+    let hyper_error = find_source::<hyper::Error>(&err);
+    if hyper_error.is_some_and(hyper::Error::is_timeout) {
+        ConnectorError::timeout(err.into())
+    } else if hyper_error.is_some_and(hyper::Error::is_user) {
+        ConnectorError::user(err.into())
+    } else if err.is_connect()
+        || find_source::<std::io::Error>(&err).is_some()
+        || hyper_error.is_some_and(|e| e.is_closed() || e.is_canceled() || e.is_incomplete_message())
+    {
+        ConnectorError::io(err.into())
+    } else {
+        ConnectorError::other(err.into(), None)
+    }
+}
+
+fn find_source<'a, E: std::error::Error + 'static>(err: &'a (dyn std::error::Error + 'static)) -> Option<&'a E> {
+    let mut next = Some(err);
+    while let Some(err) = next {
+        if let Some(found) = err.downcast_ref::<E>() {
+            return Some(found);
+        }
+        next = err.source();
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Connects at once without touching the network
+    #[derive(Clone)]
+    struct FakeConnector;
+
+    impl Service<Uri> for FakeConnector {
+        type Response = ();
+        type Error = BoxError;
+        type Future = Pin<Box<dyn Future<Output = Result<(), BoxError>> + Send>>;
+
+        fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+            Poll::Ready(Ok(()))
+        }
+
+        fn call(&mut self, _target: Uri) -> Self::Future {
+            Box::pin(async { Ok(()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn connections_wait_for_a_free_slot_on_their_host() {
+        let mut connector = HostLimited {
+            inner: FakeConnector,
+            limit: Some(Arc::new(ConnectionLimit::new(1))),
+        };
+        let wait = Duration::from_millis(50);
+        let first = connector.call("http://a:9000".parse().unwrap()).await.unwrap();
+
+        // Another host has slots of its own
+        let other = connector.call("http://b:9000".parse().unwrap()).await.unwrap();
+        let second = connector.call("http://a:9000".parse().unwrap());
+        tokio::pin!(second);
+        assert!(tokio::time::timeout(wait, &mut second).await.is_err());
+
+        // Closing the first connection frees its slot
+        drop(first);
+        assert!(tokio::time::timeout(wait, &mut second).await.is_ok());
+        drop(other);
+    }
+}
@@ -0,0 +1,37 @@
+use keyring::Entry;
+
+const KEYCHAIN_SERVICE: &str = "com.bucketviewer.app";
+const PLACEHOLDER_PREFIX: &str = "keychain:";
+
+// settings.json stores this instead of the secret once it lives in the keychain
+pub fn placeholder_for(connection_name: &str) -> String {
+    format!("{}{}", PLACEHOLDER_PREFIX, connection_name)
+}
+
+pub fn is_placeholder(secret: &str) -> bool {
+    secret.starts_with(PLACEHOLDER_PREFIX)
+}
+
+fn entry_for(connection_name: &str) -> Result<Entry, keyring::Error> {
+    Entry::new(KEYCHAIN_SERVICE, &format!("connection:{}", connection_name))
+}
+
+pub fn store_secret(connection_name: &str, secret: &str) -> Result<(), keyring::Error> {
+    entry_for(connection_name)?.set_password(secret)
+}
+
+// The keychain account a placeholder points at
+pub fn account_for(placeholder: &str) -> &str {
+    placeholder.strip_prefix(PLACEHOLDER_PREFIX).unwrap_or(placeholder)
+}
+
+pub fn resolve_placeholder(placeholder: &str) -> Result<String, keyring::Error> {
+    entry_for(account_for(placeholder))?.get_password()
+}
+
+pub fn delete_secret(connection_name: &str) -> Result<(), keyring::Error> {
+    match entry_for(connection_name)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(e),
+    }
+}
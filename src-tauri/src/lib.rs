@@ -1,11 +1,33 @@
+mod auto_refresh;
+mod bandwidth;
+mod checksum;
+mod command_error;
+mod endpoint_failover;
+mod connection_health;
+mod format;
 mod settings;
+mod settings_crypto;
+mod keychain;
+mod listing_cache;
+mod logging;
+mod mime;
+mod aws_profiles;
 mod commands;
 mod s3_service;
+mod http_client;
+mod prefix_watch;
+mod prefixes;
+mod presigned_post;
+mod providers;
 mod s3_commands;
+mod thumbnail;
+mod transfer;
+mod transfer_queue;
 
 use commands::*;
 use s3_commands::*;
 use std::sync::Arc;
+use tauri::Manager;
 
 #[tauri::command]
 fn greet(name: &str) -> String {
@@ -18,8 +40,21 @@ pub fn run() {
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_fs::init())
         .plugin(tauri_plugin_dialog::init())
+        .setup(|app| {
+            // Starts at the default level; init_settings applies the configured one
+            logging::init(app.path().app_log_dir().ok().as_deref());
+            auto_refresh::spawn(app.handle().clone());
+            connection_health::spawn(app.handle().clone());
+            let queue_path = app.path().app_data_dir()?.join("transfers.json");
+            app.manage(Arc::new(transfer_queue::TransferQueue::load(queue_path)));
+            Ok(())
+        })
         .manage(SettingsState::new(None))
         .manage(Arc::new(tokio::sync::Mutex::new(s3_service::S3ConnectionManager::new())))
+        .manage(transfer::CancellationRegistry::new())
+        .manage(transfer::TransferScheduler::default())
+        .manage(bandwidth::BandwidthState::default())
+        .manage(Arc::new(prefix_watch::PrefixWatchRegistry::new()))
         .invoke_handler(tauri::generate_handler![
             greet,
             init_settings,
@@ -31,29 +66,136 @@ pub fn run() {
             update_permissions_settings,
             add_connection,
             update_connection,
+            duplicate_connection,
+            reorder_connections,
+            move_connection_to_top,
+            get_connection_stats,
             remove_connection,
+            add_favorite,
+            remove_favorite,
+            record_recent_path,
             export_settings,
             import_settings,
             reset_settings,
             reload_settings,
+            import_aws_profiles,
+            get_log_file_path,
+            set_active_view,
+            pause_auto_refresh,
+            resume_auto_refresh,
+            list_s3_regions,
+            validate_s3_region,
+            get_s3_prefix_breadcrumbs,
+            get_s3_parent_prefix,
+            list_provider_presets,
+            resolve_provider_endpoint,
             ping_endpoint,
             test_s3_connection,
+            measure_s3_latency,
             connect_to_s3,
             disconnect_from_s3,
+            reconnect_s3,
+            disconnect_all_s3,
             list_s3_buckets,
             list_s3_buckets_with_config,
             list_s3_objects,
+            invalidate_s3_cache,
+            clear_s3_cache,
+            list_all_s3_objects,
+            get_s3_prefix_stats,
+            watch_s3_prefix,
+            unwatch_s3_prefix,
+            search_s3_objects,
             get_s3_object_info,
+            format_object_info,
+            detect_s3_object_content_type,
+            get_s3_object_range,
+            read_s3_text_object,
+            get_s3_image_thumbnail,
+            s3_select_query,
+            restore_s3_object,
+            download_s3_object,
+            upload_s3_object,
+            upload_s3_directory,
+            download_s3_directory,
+            cancel_transfer,
+            enqueue_transfer,
+            pause_transfer,
+            resume_transfer,
+            retry_transfer,
+            list_transfers,
+            set_max_concurrent_transfers,
             delete_s3_object,
+            trash_s3_object,
+            restore_s3_trash,
+            empty_s3_trash,
             delete_s3_objects,
+            delete_s3_folder,
+            validate_s3_bucket_name,
             create_s3_bucket,
+            empty_s3_bucket,
             delete_s3_bucket,
             create_s3_folder,
             generate_s3_download_url,
             generate_s3_upload_url,
+            generate_s3_presigned_post,
+            get_s3_public_url,
             copy_s3_object,
-            get_s3_bucket_location
+            copy_s3_folder,
+            move_s3_object,
+            move_s3_objects,
+            update_s3_object_metadata,
+            set_s3_object_storage_class,
+            get_s3_object_acl,
+            set_s3_object_acl,
+            make_s3_object_public,
+            get_s3_bucket_location,
+            check_s3_bucket_access,
+            get_s3_bucket_encryption,
+            set_s3_bucket_encryption,
+            get_s3_bucket_website,
+            set_s3_bucket_website,
+            delete_s3_bucket_website,
+            get_s3_bucket_logging,
+            set_s3_bucket_logging,
+            get_s3_bucket_notification,
+            set_s3_bucket_notification,
+            delete_s3_bucket_notification,
+            get_s3_bucket_capabilities,
+            get_s3_object_retention,
+            set_s3_object_retention,
+            set_s3_object_legal_hold,
+            list_s3_multipart_uploads,
+            abort_s3_multipart_upload,
+            cleanup_s3_incomplete_uploads,
+            get_s3_bucket_versioning,
+            set_s3_bucket_versioning,
+            list_s3_object_versions,
+            restore_s3_object_version,
+            remove_s3_delete_marker,
+            get_s3_object_tags,
+            set_s3_object_tags,
+            clear_s3_object_tags,
+            get_s3_bucket_tags,
+            set_s3_bucket_tags,
+            delete_s3_bucket_tags
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app_handle, event| {
+            // Drop every cached client, and the credentials it holds, on the way out, and
+            // save the connection stats recorded since the last settings save
+            if let tauri::RunEvent::Exit = event {
+                let s3_state = app_handle.state::<S3ConnectionState>();
+                let settings_state = app_handle.state::<SettingsState>();
+                tauri::async_runtime::block_on(async {
+                    s3_state.lock().await.clear_connections();
+                    if let Some(manager) = settings_state.lock().await.as_mut() {
+                        if let Err(e) = manager.flush_stats().await {
+                            tracing::warn!("Failed to save connection stats: {}", e);
+                        }
+                    }
+                });
+            }
+        });
 }
\ No newline at end of file
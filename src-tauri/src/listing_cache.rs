@@ -0,0 +1,75 @@
+use crate::s3_service::ListObjectsResponse;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_LISTING_CACHE_TTL_SECS: u64 = 30;
+const CACHE_CAPACITY: usize = 256;
+
+// Everything that changes what a single ListObjectsV2 page contains
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ListingKey {
+    pub bucket: String,
+    pub prefix: String,
+    pub delimiter: Option<String>,
+    pub max_keys: Option<i32>,
+    pub continuation_token: Option<String>,
+}
+
+struct CachedListing {
+    key: ListingKey,
+    response: ListObjectsResponse,
+    expires_at: Instant,
+}
+
+// Recently listed pages for one connection, least recently used first
+#[derive(Default)]
+pub struct ListingCache {
+    entries: Mutex<VecDeque<CachedListing>>,
+}
+
+impl ListingCache {
+    pub fn get(&self, key: &ListingKey) -> Option<ListObjectsResponse> {
+        let mut entries = self.entries.lock().unwrap();
+        let now = Instant::now();
+        entries.retain(|entry| entry.expires_at > now);
+
+        let index = entries.iter().position(|entry| entry.key == *key)?;
+        let entry = entries.remove(index)?;
+        let response = entry.response.clone();
+        entries.push_back(entry);
+        Some(response)
+    }
+
+    pub fn insert(&self, key: ListingKey, response: ListObjectsResponse, ttl: Duration) {
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|entry| entry.key != key);
+        if entries.len() >= CACHE_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back(CachedListing {
+            key,
+            response,
+            expires_at: Instant::now() + ttl,
+        });
+    }
+
+    // Drops every page of `bucket` that could include `path`, or that lists something
+    // under it. A key invalidates the listings of all its parent prefixes; a folder
+    // prefix also invalidates the listings inside it. None drops the whole bucket.
+    pub fn invalidate(&self, bucket: &str, path: Option<&str>) {
+        self.entries.lock().unwrap().retain(|entry| {
+            if entry.key.bucket != bucket {
+                return true;
+            }
+            match path {
+                Some(path) => !path.starts_with(&entry.key.prefix) && !entry.key.prefix.starts_with(path),
+                None => false,
+            }
+        });
+    }
+
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear();
+    }
+}
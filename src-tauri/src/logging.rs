@@ -0,0 +1,118 @@
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use tracing::level_filters::LevelFilter;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::prelude::*;
+use tracing_subscriber::{fmt, reload, EnvFilter, Registry};
+
+pub const DEFAULT_LOG_LEVEL: &str = "info";
+pub const REDACTED: &str = "****";
+const LOG_FILE_NAME: &str = "bucketviewer.log";
+// The previous log is kept as bucketviewer.log.old once the file grows past this
+const MAX_LOG_FILE_BYTES: u64 = 10 * 1024 * 1024;
+
+struct Logging {
+    filter: reload::Handle<EnvFilter, Registry>,
+    log_file: Option<PathBuf>,
+    // Flushes the background file writer when dropped, so it must live as long as the app
+    _guard: Option<WorkerGuard>,
+}
+
+static LOGGING: OnceLock<Logging> = OnceLock::new();
+
+// Installs the global subscriber: stdout plus a file in `log_dir` when one is available.
+// The level starts at the default until settings are loaded and apply their own.
+pub fn init(log_dir: Option<&Path>) {
+    if LOGGING.get().is_some() {
+        return;
+    }
+
+    let (filter, filter_handle) = reload::Layer::new(filter_for(LevelFilter::INFO));
+
+    let mut log_file = None;
+    let mut guard = None;
+    let file_layer = log_dir.and_then(|dir| match open_log_file(dir) {
+        Ok((path, file)) => {
+            let (writer, writer_guard) = tracing_appender::non_blocking(file);
+            log_file = Some(path);
+            guard = Some(writer_guard);
+            Some(fmt::layer().with_writer(writer).with_ansi(false))
+        }
+        Err(e) => {
+            eprintln!("Failed to open log file in {}: {}", dir.display(), e);
+            None
+        }
+    });
+
+    let subscriber = Registry::default()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer);
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Failed to install logger: {}", e);
+        return;
+    }
+
+    let _ = LOGGING.set(Logging { filter: filter_handle, log_file, _guard: guard });
+}
+
+pub fn parse_level(level: &str) -> Result<LevelFilter, String> {
+    level
+        .trim()
+        .parse::<LevelFilter>()
+        .map_err(|_| format!("Invalid log level '{}', expected one of off, error, warn, info, debug, trace", level))
+}
+
+pub fn set_level(level: &str) -> Result<(), String> {
+    let level = parse_level(level)?;
+    if let Some(logging) = LOGGING.get() {
+        logging
+            .filter
+            .reload(filter_for(level))
+            .map_err(|e| format!("Failed to change log level: {}", e))?;
+    }
+    Ok(())
+}
+
+pub fn log_file_path() -> Option<PathBuf> {
+    LOGGING.get().and_then(|logging| logging.log_file.clone())
+}
+
+// Keeps just enough of a credential to tell keys apart in the logs
+pub fn redact(secret: &str) -> String {
+    let visible: String = secret.chars().take(4).collect();
+    if secret.chars().count() <= 8 {
+        REDACTED.to_string()
+    } else {
+        format!("{}{}", visible, REDACTED)
+    }
+}
+
+// Drops the user name and password from a URL such as a proxy with credentials
+pub fn redact_url(url: &str) -> String {
+    match url::Url::parse(url) {
+        Ok(mut parsed) if !parsed.username().is_empty() || parsed.password().is_some() => {
+            let _ = parsed.set_username("");
+            let _ = parsed.set_password(None);
+            parsed.to_string()
+        }
+        Ok(_) => url.to_string(),
+        Err(_) => REDACTED.to_string(),
+    }
+}
+
+// The level applies to this crate; dependencies (SDK, hyper) only log warnings and up
+fn filter_for(level: LevelFilter) -> EnvFilter {
+    let dependency_level = std::cmp::min(level, LevelFilter::WARN);
+    EnvFilter::new(format!("{},bucketviewer_lib={}", dependency_level, level))
+}
+
+fn open_log_file(dir: &Path) -> std::io::Result<(PathBuf, std::fs::File)> {
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(LOG_FILE_NAME);
+    if std::fs::metadata(&path).map(|m| m.len() > MAX_LOG_FILE_BYTES).unwrap_or(false) {
+        std::fs::rename(&path, dir.join(format!("{}.old", LOG_FILE_NAME)))?;
+    }
+    let file = std::fs::OpenOptions::new().create(true).append(true).open(&path)?;
+    Ok((path, file))
+}
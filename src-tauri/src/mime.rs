@@ -0,0 +1,142 @@
+use std::path::Path;
+use tokio::io::AsyncReadExt;
+
+pub const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+// How many leading bytes sniffing looks at
+pub const SNIFF_LENGTH: usize = 512;
+
+// Signatures at the start of common file formats, checked in order
+const SIGNATURES: &[(&[u8], &str)] = &[
+    (b"\x89PNG\r\n\x1a\n", "image/png"),
+    (b"\xff\xd8\xff", "image/jpeg"),
+    (b"GIF87a", "image/gif"),
+    (b"GIF89a", "image/gif"),
+    (b"%PDF-", "application/pdf"),
+    (b"PK\x03\x04", "application/zip"),
+    (b"\x1f\x8b", "application/gzip"),
+    (b"BZh", "application/x-bzip2"),
+    (b"\xfd7zXZ\x00", "application/x-xz"),
+    (b"7z\xbc\xaf\x27\x1c", "application/x-7z-compressed"),
+    (b"\x28\xb5\x2f\xfd", "application/zstd"),
+    (b"OggS", "audio/ogg"),
+    (b"fLaC", "audio/flac"),
+    (b"ID3", "audio/mpeg"),
+    (b"\x1a\x45\xdf\xa3", "video/webm"),
+    (b"wOFF", "font/woff"),
+    (b"wOF2", "font/woff2"),
+    (b"\x00asm", "application/wasm"),
+    (b"SQLite format 3\x00", "application/vnd.sqlite3"),
+];
+
+// Content type from the extension of a local path or an S3 key
+pub fn guess_content_type(path_or_key: &str) -> Option<String> {
+    mime_guess::from_path(path_or_key).first().map(|mime| mime.to_string())
+}
+
+// Content type from the first bytes of a file, for names without a known extension.
+// Anything that decodes as UTF-8 without control characters is treated as text.
+pub fn sniff_content_type(bytes: &[u8]) -> Option<&'static str> {
+    if let Some((_, content_type)) = SIGNATURES.iter().find(|(signature, _)| bytes.starts_with(signature)) {
+        return Some(content_type);
+    }
+    // Containers whose type is named a few bytes in
+    match bytes.get(..12) {
+        Some([b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P']) => return Some("image/webp"),
+        Some([b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'A', b'V', b'E']) => return Some("audio/wav"),
+        Some([_, _, _, _, b'f', b't', b'y', b'p', ..]) => return Some("video/mp4"),
+        _ => {}
+    }
+
+    if bytes.is_empty() {
+        return None;
+    }
+    // A multi-byte character may be cut off at the end of the sample
+    let text = match std::str::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(e) if e.error_len().is_none() => std::str::from_utf8(&bytes[..e.valid_up_to()]).unwrap_or_default(),
+        Err(_) => return None,
+    };
+    let is_text = text.chars().all(|c| !c.is_control() || c.is_whitespace());
+    if !is_text {
+        return None;
+    }
+    let trimmed = text.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        Some("application/json")
+    } else if trimmed.starts_with("<?xml") {
+        Some("application/xml")
+    } else if trimmed.get(..15).is_some_and(|start| start.eq_ignore_ascii_case("<!doctype html>"))
+        || trimmed.starts_with("<html")
+    {
+        Some("text/html")
+    } else {
+        Some("text/plain")
+    }
+}
+
+// Content type to upload a local file with: from its extension, else from its first bytes
+pub async fn content_type_for_file(path: &Path) -> String {
+    if let Some(content_type) = guess_content_type(&path.to_string_lossy()) {
+        return content_type;
+    }
+
+    let mut buffer = vec![0u8; SNIFF_LENGTH];
+    let sniffed = match tokio::fs::File::open(path).await {
+        Ok(mut file) => match file.read(&mut buffer).await {
+            Ok(read) => sniff_content_type(&buffer[..read]),
+            Err(_) => None,
+        },
+        Err(_) => None,
+    };
+    sniffed.unwrap_or(DEFAULT_CONTENT_TYPE).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sniffs_magic_bytes() {
+        let cases: &[(&[u8], Option<&str>)] = &[
+            (b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR", Some("image/png")),
+            (b"\xff\xd8\xff\xe0\x00\x10JFIF", Some("image/jpeg")),
+            (b"%PDF-1.7\n", Some("application/pdf")),
+            (b"PK\x03\x04\x14\x00\x00\x00", Some("application/zip")),
+            (b"RIFF\x24\x00\x00\x00WEBPVP8 ", Some("image/webp")),
+            (b"\x00\x00\x00\x18ftypmp42", Some("video/mp4")),
+            (b"", None),
+            (b"\x00\x01\x02\x03\xfe\xff", None),
+            (b"  {\"key\": 1}", Some("application/json")),
+            (b"<!DOCTYPE html><html>", Some("text/html")),
+            (b"plain text\n", Some("text/plain")),
+            // A multi-byte character cut off by the sample length is still text
+            (b"caf\xc3", Some("text/plain")),
+        ];
+        for (bytes, expected) in cases {
+            assert_eq!(sniff_content_type(bytes), *expected, "sniffing {:?}", bytes);
+        }
+    }
+
+    #[tokio::test]
+    async fn file_types_come_from_the_extension_before_the_content() {
+        let dir = std::env::temp_dir().join(format!("bucketviewer-mime-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cases: &[(&str, &[u8], &str)] = &[
+            ("image.png", b"not really a png", "image/png"),
+            ("image", b"\x89PNG\r\n\x1a\n", "image/png"),
+            ("data.json", b"\x00\x01\x02\x03", "application/json"),
+            ("data", b"\x00\x01\x02\x03", DEFAULT_CONTENT_TYPE),
+            ("empty", b"", DEFAULT_CONTENT_TYPE),
+            ("missing", b"", DEFAULT_CONTENT_TYPE),
+        ];
+        for (name, content, expected) in cases {
+            let path = dir.join(name);
+            if *name != "missing" {
+                std::fs::write(&path, content).unwrap();
+            }
+            assert_eq!(content_type_for_file(&path).await, *expected, "content type of {}", name);
+        }
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,345 @@
+use crate::s3_service::{ObjectInfo, S3Error, S3Service};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{Emitter, Window};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, warn};
+
+pub const OBJECTS_ADDED_EVENT: &str = "objects-added";
+pub const OBJECTS_REMOVED_EVENT: &str = "objects-removed";
+pub const OBJECTS_CHANGED_EVENT: &str = "objects-changed";
+
+// Polling faster than this only burns requests
+pub const MIN_WATCH_INTERVAL_SECS: u64 = 5;
+
+// Objects listed per poll at most; changes past the last one listed go unnoticed
+const MAX_WATCHED_OBJECTS: usize = 10_000;
+
+pub type PrefixWatchState = Arc<PrefixWatchRegistry>;
+
+// Payload of the objects-* events. Removed objects are reported as last seen.
+// `truncated` means the prefix holds more than MAX_WATCHED_OBJECTS objects and only the
+// first ones are watched.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PrefixChanges {
+    pub watch_id: String,
+    pub bucket: String,
+    pub prefix: String,
+    pub objects: Vec<ObjectInfo>,
+    pub truncated: bool,
+}
+
+type Changes = (Vec<ObjectInfo>, Vec<ObjectInfo>, Vec<ObjectInfo>);
+
+// One poll's listing. A truncated listing only speaks for keys up to the last one it
+// got, so later keys are neither added nor removed.
+struct Snapshot {
+    objects: HashMap<String, ObjectInfo>,
+    listed_up_to: Option<String>,
+}
+
+impl Snapshot {
+    fn new(objects: Vec<ObjectInfo>, truncated: bool) -> Self {
+        let listed_up_to = truncated.then(|| objects.iter().map(|object| object.key.clone()).max()).flatten();
+        Self {
+            objects: objects.into_iter().map(|object| (object.key.clone(), object)).collect(),
+            listed_up_to,
+        }
+    }
+
+    fn covers(&self, key: &str) -> bool {
+        self.listed_up_to.as_deref().is_none_or(|last| key <= last)
+    }
+}
+
+struct PrefixWatch {
+    connection_name: String,
+    cancel_token: CancellationToken,
+    // None until the first listing completes; that listing is the baseline and emits nothing
+    snapshot: Option<Snapshot>,
+}
+
+// Every running prefix watch and the last listing it saw, by watch id
+pub struct PrefixWatchRegistry {
+    watches: Mutex<HashMap<String, PrefixWatch>>,
+}
+
+impl PrefixWatchRegistry {
+    pub fn new() -> Self {
+        Self {
+            watches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Lists the prefix every `interval` and emits what changed since the previous listing.
+    // Only objects directly under the prefix are watched, not those in its subfolders.
+    // Returns the id to pass to `unwatch`.
+    pub fn watch(
+        self: &Arc<Self>,
+        window: Window,
+        service: Arc<S3Service>,
+        connection_name: &str,
+        bucket: String,
+        prefix: String,
+        interval: Duration,
+    ) -> String {
+        let watch_id = uuid::Uuid::new_v4().to_string();
+        let cancel_token = CancellationToken::new();
+        self.watches.lock().unwrap().insert(
+            watch_id.clone(),
+            PrefixWatch {
+                connection_name: connection_name.to_string(),
+                cancel_token: cancel_token.clone(),
+                snapshot: None,
+            },
+        );
+
+        let registry = Arc::clone(self);
+        let id = watch_id.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let listing = list_level(&service, &bucket, &prefix).await;
+                registry.record_listing(&window, &id, &bucket, &prefix, listing);
+
+                tokio::select! {
+                    _ = cancel_token.cancelled() => break,
+                    _ = tokio::time::sleep(interval) => {}
+                }
+            }
+            debug!("Stopped watching {}/{} ({})", bucket, prefix, id);
+        });
+
+        watch_id
+    }
+
+    pub fn unwatch(&self, watch_id: &str) -> bool {
+        match self.watches.lock().unwrap().remove(watch_id) {
+            Some(watch) => {
+                watch.cancel_token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    // Stops every watch using a connection that is going away
+    pub fn unwatch_connection(&self, connection_name: &str) {
+        self.watches.lock().unwrap().retain(|_, watch| {
+            let keep = watch.connection_name != connection_name;
+            if !keep {
+                watch.cancel_token.cancel();
+            }
+            keep
+        });
+    }
+
+    pub fn unwatch_all(&self) {
+        for (_, watch) in self.watches.lock().unwrap().drain() {
+            watch.cancel_token.cancel();
+        }
+    }
+
+    fn record_listing(
+        &self,
+        window: &Window,
+        watch_id: &str,
+        bucket: &str,
+        prefix: &str,
+        listing: Result<Snapshot, S3Error>,
+    ) {
+        let truncated = listing.as_ref().is_ok_and(|snapshot| snapshot.listed_up_to.is_some());
+        if truncated {
+            debug!(
+                "{}/{} has more than {} objects, watch {} only sees the first ones",
+                bucket, prefix, MAX_WATCHED_OBJECTS, watch_id
+            );
+        }
+        let listing = listing.map_err(|err| format!("Failed to poll {}/{} for watch {}: {}", bucket, prefix, watch_id, err));
+        let Some((added, removed, changed)) = self.update_snapshot(watch_id, listing) else {
+            return;
+        };
+
+        for (event, objects) in [
+            (OBJECTS_ADDED_EVENT, added),
+            (OBJECTS_REMOVED_EVENT, removed),
+            (OBJECTS_CHANGED_EVENT, changed),
+        ] {
+            if objects.is_empty() {
+                continue;
+            }
+            let changes = PrefixChanges {
+                watch_id: watch_id.to_string(),
+                bucket: bucket.to_string(),
+                prefix: prefix.to_string(),
+                objects,
+                truncated,
+            };
+            if let Err(e) = window.emit(event, changes) {
+                warn!("Failed to emit {} for watch {}: {}", event, watch_id, e);
+            }
+        }
+    }
+
+    // Swaps in the new listing and returns what changed, or None for the baseline listing
+    // and for watches that were stopped while the listing was in flight. A failed poll
+    // keeps the old snapshot, so nothing is reported as removed.
+    fn update_snapshot(&self, watch_id: &str, listing: Result<Snapshot, String>) -> Option<Changes> {
+        let current = match listing {
+            Ok(current) => current,
+            Err(err) => {
+                warn!("{}", err);
+                return None;
+            }
+        };
+        let mut watches = self.watches.lock().unwrap();
+        let watch = watches.get_mut(watch_id)?;
+        let previous = watch.snapshot.replace(current)?;
+        Some(diff_snapshots(&previous, watch.snapshot.as_ref()?))
+    }
+}
+
+impl Default for PrefixWatchRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Lists the objects directly under `prefix`, page by page, up to MAX_WATCHED_OBJECTS
+async fn list_level(service: &S3Service, bucket: &str, prefix: &str) -> Result<Snapshot, S3Error> {
+    let mut objects = Vec::new();
+    let mut continuation_token: Option<String> = None;
+    loop {
+        let page = service
+            .list_objects(bucket, Some(prefix), Some("/"), None, continuation_token.as_deref())
+            .await?;
+        objects.extend(page.objects.into_iter().filter(|object| !object.is_folder));
+        if objects.len() >= MAX_WATCHED_OBJECTS {
+            let truncated = objects.len() > MAX_WATCHED_OBJECTS || page.is_truncated;
+            objects.truncate(MAX_WATCHED_OBJECTS);
+            return Ok(Snapshot::new(objects, truncated));
+        }
+        if page.next_continuation_token.is_none() {
+            return Ok(Snapshot::new(objects, false));
+        }
+        continuation_token = page.next_continuation_token;
+    }
+}
+
+// Returns the (added, removed, changed) objects among the keys both listings cover; an
+// object changed if its ETag or modification time differs
+fn diff_snapshots(previous: &Snapshot, current: &Snapshot) -> Changes {
+    let mut added = Vec::new();
+    let mut changed = Vec::new();
+    for (key, object) in current.objects.iter().filter(|(key, _)| previous.covers(key)) {
+        match previous.objects.get(key) {
+            None => added.push(object.clone()),
+            Some(old) if old.etag != object.etag || old.last_modified != object.last_modified => {
+                changed.push(object.clone())
+            }
+            Some(_) => {}
+        }
+    }
+
+    let removed = previous
+        .objects
+        .iter()
+        .filter(|(key, _)| current.covers(key) && !current.objects.contains_key(*key))
+        .map(|(_, object)| object.clone())
+        .collect();
+
+    (added, removed, changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn object(key: &str, etag: &str) -> ObjectInfo {
+        ObjectInfo {
+            key: key.to_string(),
+            size: Some(1),
+            last_modified: Some("2024-01-01T00:00:00Z".to_string()),
+            etag: Some(etag.to_string()),
+            storage_class: None,
+            content_type: None,
+            is_folder: false,
+            restore_status: None,
+            lock_mode: None,
+            lock_retain_until: None,
+            legal_hold: None,
+            checksum: None,
+        }
+    }
+
+    fn keys(objects: &[ObjectInfo]) -> Vec<&str> {
+        let mut keys: Vec<&str> = objects.iter().map(|object| object.key.as_str()).collect();
+        keys.sort();
+        keys
+    }
+
+    fn registry_with_watch(watch_id: &str) -> PrefixWatchRegistry {
+        let registry = PrefixWatchRegistry::new();
+        registry.watches.lock().unwrap().insert(
+            watch_id.to_string(),
+            PrefixWatch {
+                connection_name: "minio".to_string(),
+                cancel_token: CancellationToken::new(),
+                snapshot: None,
+            },
+        );
+        registry
+    }
+
+    #[test]
+    fn diff_reports_added_removed_and_modified_objects() {
+        let previous = Snapshot::new(vec![object("a", "1"), object("b", "1"), object("c", "1")], false);
+        let current = Snapshot::new(vec![object("a", "1"), object("b", "2"), object("d", "1")], false);
+
+        let (added, removed, changed) = diff_snapshots(&previous, &current);
+
+        assert_eq!(keys(&added), ["d"]);
+        assert_eq!(keys(&removed), ["c"]);
+        assert_eq!(keys(&changed), ["b"]);
+    }
+
+    #[test]
+    fn truncated_listings_only_compare_the_keys_they_cover() {
+        let previous = Snapshot::new(vec![object("a", "1"), object("c", "1"), object("e", "1")], false);
+        let current = Snapshot::new(vec![object("b", "1"), object("c", "1")], true);
+
+        let (added, removed, changed) = diff_snapshots(&previous, &current);
+
+        assert_eq!(keys(&added), ["b"]);
+        assert_eq!(keys(&removed), ["a"]);
+        assert!(changed.is_empty());
+    }
+
+    #[test]
+    fn first_listing_is_the_baseline() {
+        let registry = registry_with_watch("w");
+
+        assert!(registry.update_snapshot("w", Ok(Snapshot::new(vec![object("a", "1")], false))).is_none());
+        let (added, _, _) = registry
+            .update_snapshot("w", Ok(Snapshot::new(vec![object("a", "1"), object("b", "1")], false)))
+            .unwrap();
+        assert_eq!(keys(&added), ["b"]);
+        assert!(registry.update_snapshot("stopped", Ok(Snapshot::new(Vec::new(), false))).is_none());
+    }
+
+    #[test]
+    fn failed_poll_keeps_the_old_snapshot() {
+        let registry = registry_with_watch("w");
+        registry.update_snapshot("w", Ok(Snapshot::new(vec![object("a", "1"), object("b", "1")], false)));
+
+        assert!(registry.update_snapshot("w", Err("timed out".to_string())).is_none());
+        let (added, removed, changed) = registry
+            .update_snapshot("w", Ok(Snapshot::new(vec![object("a", "1")], false)))
+            .unwrap();
+
+        assert!(added.is_empty());
+        assert_eq!(keys(&removed), ["b"]);
+        assert!(changed.is_empty());
+    }
+}
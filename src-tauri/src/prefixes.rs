@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+
+// One navigable level of a prefix. `prefix` is what to list to open it; the root has
+// an empty name and prefix.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Breadcrumb {
+    pub name: String,
+    pub prefix: String,
+}
+
+// "photos/2024/" and "photos/2024" both give root, "photos" ("photos/") and "2024"
+// ("photos/2024/"). Only the one trailing slash is dropped: S3 keys may start with a
+// slash or contain "//", and those empty names are real levels.
+pub fn breadcrumbs(prefix: &str) -> Vec<Breadcrumb> {
+    let mut crumbs = vec![Breadcrumb { name: String::new(), prefix: String::new() }];
+    if prefix.is_empty() {
+        return crumbs;
+    }
+    let trimmed = prefix.strip_suffix('/').unwrap_or(prefix);
+
+    let mut cumulative = String::new();
+    for segment in trimmed.split('/') {
+        cumulative.push_str(segment);
+        cumulative.push('/');
+        crumbs.push(Breadcrumb { name: segment.to_string(), prefix: cumulative.clone() });
+    }
+    crumbs
+}
+
+// The folder containing `prefix`, with its trailing slash: "a/b/c/" and "a/b/c" both
+// give "a/b/", "a/" gives "", and the root is its own parent.
+pub fn parent_prefix(prefix: &str) -> String {
+    let trimmed = prefix.strip_suffix('/').unwrap_or(prefix);
+    match trimmed.rfind('/') {
+        Some(index) => trimmed[..=index].to_string(),
+        None => String::new(),
+    }
+}
+
+// The deepest folder every key sits in, e.g. "a/b/" for "a/b/c.txt" and "a/b/d/e.txt",
+// or "" when the keys share no folder
+pub fn common_prefix<'a>(keys: impl IntoIterator<Item = &'a str>) -> String {
+    let mut common: Option<String> = None;
+    for key in keys {
+        let parent = parent_prefix(key);
+        common = Some(match common {
+            None => parent,
+            Some(current) => {
+                // Compared by char so the cut never lands inside a multi-byte character
+                let shared: usize = current
+                    .chars()
+                    .zip(parent.chars())
+                    .take_while(|(a, b)| a == b)
+                    .map(|(a, _)| a.len_utf8())
+                    .sum();
+                match current[..shared].rfind('/') {
+                    Some(index) => current[..=index].to_string(),
+                    None => String::new(),
+                }
+            }
+        });
+    }
+    common.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parent_of_root_and_top_level_is_root() {
+        assert_eq!(parent_prefix(""), "");
+        assert_eq!(parent_prefix("a"), "");
+        assert_eq!(parent_prefix("a/"), "");
+    }
+
+    #[test]
+    fn parent_of_nested_prefix_ignores_trailing_slash() {
+        assert_eq!(parent_prefix("a/b/c"), "a/b/");
+        assert_eq!(parent_prefix("a/b/c/"), "a/b/");
+        assert_eq!(parent_prefix("a/b/"), "a/");
+    }
+
+    #[test]
+    fn parent_keeps_empty_levels() {
+        assert_eq!(parent_prefix("/a"), "/");
+        assert_eq!(parent_prefix("a//b"), "a//");
+        assert_eq!(parent_prefix("a//"), "a/");
+    }
+
+    #[test]
+    fn common_prefix_of_non_ascii_keys() {
+        // é and è share their first UTF-8 byte
+        assert_eq!(common_prefix(["a/é/x", "a/è/y"]), "a/");
+        assert_eq!(common_prefix(["写真/2024/a.jpg", "写真/2024/b.jpg"]), "写真/2024/");
+        assert_eq!(common_prefix(["写真/a.jpg", "写本/b.jpg"]), "");
+    }
+
+    #[test]
+    fn common_prefix_of_folders() {
+        assert_eq!(common_prefix(["a/b/c.txt", "a/b/d/e.txt"]), "a/b/");
+        assert_eq!(common_prefix(["a/b/c.txt"]), "a/b/");
+        assert_eq!(common_prefix(["a.txt", "a/b.txt"]), "");
+        assert_eq!(common_prefix(Vec::<&str>::new()), "");
+    }
+}
@@ -0,0 +1,135 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ProviderPreset {
+    pub name: &'static str,
+    // "{region}" and "{account_id}" are substituted by resolve_endpoint
+    pub endpoint_template: &'static str,
+    pub default_region: &'static str,
+    pub force_path_style: bool,
+    // Known regions; empty where any name goes (MinIO)
+    pub regions: &'static [&'static str],
+}
+
+// Names match the service types offered in the connection form
+pub const PROVIDER_PRESETS: &[ProviderPreset] = &[
+    ProviderPreset {
+        name: "Amazon S3",
+        endpoint_template: "https://s3.{region}.amazonaws.com",
+        default_region: "us-east-1",
+        force_path_style: false,
+        regions: &[
+            "us-east-1", "us-east-2", "us-west-1", "us-west-2", "af-south-1", "ap-east-1", "ap-east-2",
+            "ap-south-1", "ap-south-2", "ap-northeast-1", "ap-northeast-2", "ap-northeast-3",
+            "ap-southeast-1", "ap-southeast-2", "ap-southeast-3", "ap-southeast-4", "ap-southeast-5",
+            "ap-southeast-7", "ca-central-1", "ca-west-1", "eu-central-1", "eu-central-2", "eu-west-1",
+            "eu-west-2", "eu-west-3", "eu-south-1", "eu-south-2", "eu-north-1", "il-central-1",
+            "me-south-1", "me-central-1", "mx-central-1", "sa-east-1", "us-gov-east-1", "us-gov-west-1",
+            "cn-north-1", "cn-northwest-1",
+        ],
+    },
+    ProviderPreset {
+        name: "MinIO",
+        endpoint_template: "http://localhost:9000",
+        default_region: "us-east-1",
+        force_path_style: true,
+        regions: &[],
+    },
+    ProviderPreset {
+        name: "Wasabi",
+        endpoint_template: "https://s3.{region}.wasabisys.com",
+        default_region: "us-east-1",
+        force_path_style: false,
+        regions: &[
+            "us-east-1", "us-east-2", "us-central-1", "us-west-1", "us-west-2", "ca-central-1",
+            "eu-central-1", "eu-central-2", "eu-west-1", "eu-west-2", "eu-west-3", "eu-south-1",
+            "ap-northeast-1", "ap-northeast-2", "ap-southeast-1", "ap-southeast-2",
+        ],
+    },
+    ProviderPreset {
+        name: "Backblaze B2",
+        endpoint_template: "https://s3.{region}.backblazeb2.com",
+        default_region: "us-west-004",
+        force_path_style: false,
+        regions: &["us-west-000", "us-west-001", "us-west-002", "us-west-004", "us-east-005", "eu-central-003", "ca-east-006"],
+    },
+    ProviderPreset {
+        name: "DigitalOcean Spaces",
+        endpoint_template: "https://{region}.digitaloceanspaces.com",
+        default_region: "nyc3",
+        force_path_style: false,
+        regions: &["nyc3", "sfo2", "sfo3", "ams3", "sgp1", "fra1", "syd1", "blr1", "lon1", "tor1", "atl1"],
+    },
+    ProviderPreset {
+        name: "Cloudflare R2",
+        endpoint_template: "https://{account_id}.r2.cloudflarestorage.com",
+        default_region: "auto",
+        force_path_style: true,
+        regions: &["auto"],
+    },
+    ProviderPreset {
+        name: "Google Cloud Storage",
+        endpoint_template: "https://storage.googleapis.com",
+        default_region: "auto",
+        force_path_style: true,
+        regions: &["auto"],
+    },
+];
+
+pub fn find_preset(service_type: &str) -> Option<&'static ProviderPreset> {
+    let normalize = |value: &str| value.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    let wanted = normalize(service_type);
+    PROVIDER_PRESETS.iter().find(|preset| normalize(preset.name) == wanted)
+}
+
+// Builds the endpoint for `service_type`. An empty region uses the preset's default;
+// presets keyed by account (R2) fail without an account id.
+pub fn resolve_endpoint(service_type: &str, region: &str, account_id: Option<&str>) -> Result<String, String> {
+    let preset = find_preset(service_type)
+        .ok_or_else(|| format!("No endpoint preset for service type '{}'", service_type))?;
+
+    let region = region.trim();
+    let region = if region.is_empty() { preset.default_region } else { region };
+    if !region.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return Err(format!("Invalid region '{}'", region));
+    }
+
+    let mut endpoint = preset.endpoint_template.replace("{region}", region);
+    if endpoint.contains("{account_id}") {
+        let account_id = account_id
+            .map(str::trim)
+            .filter(|id| !id.is_empty())
+            .ok_or_else(|| format!("{} requires an account ID", preset.name))?;
+        if !account_id.chars().all(|c| c.is_ascii_alphanumeric()) {
+            return Err(format!("Invalid account ID '{}'", account_id));
+        }
+        endpoint = endpoint.replace("{account_id}", account_id);
+    }
+    Ok(endpoint)
+}
+
+// Regions offered for `service_type`, or AWS's when it has no preset
+pub fn known_regions(service_type: Option<&str>) -> &'static [&'static str] {
+    match service_type.and_then(find_preset) {
+        Some(preset) => preset.regions,
+        None => PROVIDER_PRESETS[0].regions,
+    }
+}
+
+// A warning if `region` isn't one `service_type` is known to have, with the closest
+// match when it only differs in punctuation or case ("us-east1"). Providers without a
+// fixed list, and custom ones, accept anything.
+pub fn region_warning(service_type: &str, region: &str) -> Option<String> {
+    let preset = find_preset(service_type)?;
+    let region = region.trim();
+    if region.is_empty() || preset.regions.is_empty() || preset.regions.contains(&region) {
+        return None;
+    }
+
+    let normalize = |value: &str| value.chars().filter(|c| c.is_ascii_alphanumeric()).collect::<String>().to_lowercase();
+    let wanted = normalize(region);
+    match preset.regions.iter().find(|known| normalize(known) == wanted) {
+        Some(known) => Some(format!("Unknown {} region '{}', did you mean '{}'?", preset.name, region, known)),
+        None => Some(format!("Unknown {} region '{}'", preset.name, region)),
+    }
+}
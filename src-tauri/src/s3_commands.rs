@@ -1,502 +1,2787 @@
-use crate::s3_service::{S3Service, S3Config, S3ConnectionManager, BucketInfo, ObjectInfo, ListObjectsResponse, PresignedUrlResponse};
-use crate::settings::ConnectionConfig;
+use crate::s3_service::{bucket_name_violations, object_url, discard_partial_download, validate_bucket_name, CopyOptions, Preconditions, EmptyBucketResult, S3Service, S3Config, S3ConnectionManager, S3Error, TransferContext, DEFAULT_MULTIPART_THRESHOLD, DEFAULT_COPY_CONCURRENCY, DEFAULT_UPLOAD_CONCURRENCY, DEFAULT_DOWNLOAD_CONCURRENCY, DirectoryDownloadResult, TRASH_PREFIX, MAX_LATENCY_SAMPLES, BucketAccess, BucketCapabilities, EncryptionConfig, WebsiteConfig, LoggingConfig, NotificationConfig, ObjectAcl, ObjectEncryption, SseAlgorithm, UploadResult, MultipartUploadInfo, ObjectRetention, RetentionMode, LatencyStats, EndpointProbe, BatchResult, DeleteResult, KeyMove, KeyPattern, BucketInfo, ObjectInfo, ObjectRangeResponse, TextObjectResponse, SelectInput, SelectOutputFormat, SelectResult, DEFAULT_TEXT_PREVIEW_BYTES, RestoreTier, ListObjectsResponse, ListAllObjectsResponse, SortField, SortDirection, sort_objects, PrefixStats, SearchObjectsResponse, ObjectTag, BucketVersioningStatus, ListObjectVersionsResponse, PresignedUrlResponse, PresignedPostResponse};
+use crate::auto_refresh;
+use crate::bandwidth::BandwidthState;
+use crate::checksum::ChecksumAlgorithm;
+use crate::command_error::{CommandError, ErrorCode};
+use crate::connection_health::{self, ConnectionHealthEvent, CONNECTION_LOST_EVENT, CONNECTION_RESTORED_EVENT, MAX_RECONNECT_ATTEMPTS};
+use crate::commands::SettingsState;
+use crate::format::{self, FormattedObjectInfo};
+use crate::mime;
+use crate::prefix_watch::{PrefixWatchState, MIN_WATCH_INTERVAL_SECS};
+use crate::prefixes::{self, Breadcrumb};
+use crate::providers;
+use crate::thumbnail::{ImageThumbnail, DEFAULT_THUMBNAIL_DIMENSION};
+use crate::settings::{AppSettings, ConnectionConfig, GeneralSettings, MAX_PAGE_SIZE};
+use crate::transfer_queue::{emit_status, TransferJob, TransferKind, TransferQueueState, TransferStatus};
+use crate::transfer::{CancellationRegistry, ProgressReporter, TransferPermit, TransferScheduler, SearchProgress, SelectRecords, SELECT_RECORDS_EVENT, PREFIX_STATS_PROGRESS_EVENT, SEARCH_PROGRESS_EVENT, EMPTY_BUCKET_PROGRESS_EVENT};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Emitter, Manager, State, Window};
 use tokio::sync::Mutex as TokioMutex;
-use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+use std::time::{Duration, Instant};
+use tracing::{debug, error, info, warn};
 
 pub type S3ConnectionState = Arc<TokioMutex<S3ConnectionManager>>;
 
+pub const CONNECTED_EVENT: &str = "connected";
+pub const CONNECT_FAILED_EVENT: &str = "connect-failed";
+
+fn to_s3_config(connection_config: ConnectionConfig) -> S3Config {
+    let mut endpoint = connection_config.endpoint;
+    let mut region = connection_config.region;
+    let mut force_path_style = connection_config.force_path_style;
+
+    // Connections saved without an endpoint fall back to their service type's preset.
+    // An explicit endpoint keeps the existing path-style defaults.
+    if endpoint.trim().is_empty() {
+        if let Some(preset) = providers::find_preset(&connection_config.service_type) {
+            if let Ok(resolved) = providers::resolve_endpoint(preset.name, &region, None) {
+                endpoint = resolved;
+                force_path_style = force_path_style.or(Some(preset.force_path_style));
+            }
+            if region.trim().is_empty() {
+                region = preset.default_region.to_string();
+            }
+        }
+    }
+
+    S3Config {
+        endpoint,
+        access_key: connection_config.access_key,
+        secret_key: connection_config.secret_key,
+        region,
+        bucket: None,
+        force_path_style,
+        allow_invalid_certs: connection_config.allow_invalid_certs,
+        session_token: connection_config.session_token,
+        expiration: connection_config.expiration,
+        timeout_secs: connection_config.timeout_secs,
+        max_retries: connection_config.max_retries,
+        proxy_url: connection_config.proxy_url,
+        max_connections: connection_config.max_connections,
+        fallback_endpoints: connection_config.fallback_endpoints,
+        anonymous: connection_config.anonymous,
+        request_payer: connection_config.request_payer,
+        use_accelerate: connection_config.use_accelerate,
+        use_dualstack: connection_config.use_dualstack,
+        use_default_credential_chain: connection_config.use_default_credential_chain,
+        profile_name: connection_config.profile_name,
+    }
+}
+
+// Returns the cached service for `connection_name`. If the connection has not been
+// established yet and a config is supplied, the service is created and cached.
+async fn get_service(
+    s3_state: &S3ConnectionState,
+    connection_name: &str,
+    connection_config: Option<ConnectionConfig>,
+) -> Result<Arc<S3Service>, CommandError> {
+    let manager = s3_state.lock().await;
+    if let Some(service) = manager.get_connection(connection_name) {
+        return Ok(service);
+    }
+
+    match connection_config {
+        Some(config) => manager
+            .get_or_create_connection(connection_name, to_s3_config(config))
+            .await
+            .map_err(|err| CommandError::with_context("Failed to create S3 service", err)),
+        None => Err(S3Error::ConnectionNotFound(connection_name.to_string()).into()),
+    }
+}
+
+// Refuses writes to connections marked read-only, either in the saved settings or in the
+// config sent along with the command, so a UI bug can't modify a protected bucket.
+async fn ensure_writable(
+    settings_state: &SettingsState,
+    connection_name: &str,
+    connection_config: Option<&ConnectionConfig>,
+) -> Result<(), CommandError> {
+    let saved_read_only = settings_state
+        .lock()
+        .await
+        .as_ref()
+        .and_then(|manager| manager.connection(connection_name).map(|conn| conn.read_only))
+        .unwrap_or(false);
+
+    if saved_read_only || connection_config.is_some_and(|config| config.read_only) {
+        return Err(CommandError::new(
+            ErrorCode::PermissionDenied,
+            format!("Connection '{}' is read-only", connection_name),
+        ));
+    }
+    Ok(())
+}
+
+// None when listing caching is turned off in the permissions settings
+async fn listing_cache_ttl(settings_state: &SettingsState) -> Option<Duration> {
+    settings_state
+        .lock()
+        .await
+        .as_ref()
+        .map(|manager| manager.permissions_settings())
+        .filter(|permissions| permissions.enable_caching && permissions.cache_ttl_secs > 0)
+        .map(|permissions| Duration::from_secs(permissions.cache_ttl_secs))
+}
+
+async fn default_page_size(settings_state: &SettingsState) -> i32 {
+    settings_state
+        .lock()
+        .await
+        .as_ref()
+        .map_or(MAX_PAGE_SIZE, |manager| manager.layout_settings().default_page_size)
+        .clamp(1, MAX_PAGE_SIZE)
+}
+
+// Enforces the presign expiry cap from the settings; the 7-day SigV4 limit is checked
+// by the service
+async fn check_presign_policy(settings_state: &SettingsState, expires_in_secs: u64) -> Result<(), CommandError> {
+    let limit = settings_state
+        .lock()
+        .await
+        .as_ref()
+        .and_then(|manager| manager.permissions_settings().max_presign_expiry_secs);
+    match limit {
+        Some(limit) if expires_in_secs > limit => Err(CommandError::new(
+            ErrorCode::ConfigurationError,
+            format!("Expiry of {} seconds exceeds the maximum of {} seconds set in settings", expires_in_secs, limit),
+        )),
+        _ => Ok(()),
+    }
+}
+
+async fn trash_enabled(settings_state: &SettingsState) -> bool {
+    settings_state
+        .lock()
+        .await
+        .as_ref()
+        .is_some_and(|manager| manager.general_settings().use_trash)
+}
+
+// With confirm_before_delete on, deletes must say they were confirmed by the user
+async fn ensure_delete_confirmed(settings_state: &SettingsState, confirmed: Option<bool>) -> Result<(), CommandError> {
+    let confirmation_required = settings_state
+        .lock()
+        .await
+        .as_ref()
+        .map(|manager| manager.general_settings().confirm_before_delete)
+        .unwrap_or(true);
+
+    if confirmation_required && confirmed != Some(true) {
+        return Err(CommandError::new(
+            ErrorCode::PermissionDenied,
+            "Deletion was not confirmed",
+        ));
+    }
+    Ok(())
+}
+
+// The naming rules `bucket` breaks, so the UI can flag them while the name is typed
+#[tauri::command]
+pub fn validate_s3_bucket_name(bucket: String) -> Vec<String> {
+    bucket_name_violations(&bucket)
+}
+
+// The levels of `prefix` from the root down, for breadcrumb navigation
+#[tauri::command]
+pub fn get_s3_prefix_breadcrumbs(prefix: String) -> Vec<Breadcrumb> {
+    prefixes::breadcrumbs(&prefix)
+}
+
+#[tauri::command]
+pub fn get_s3_parent_prefix(prefix: String) -> String {
+    prefixes::parent_prefix(&prefix)
+}
+
+// Known regions of the provider, AWS's by default, for suggesting while typing
+#[tauri::command]
+pub fn list_s3_regions(service_type: Option<String>) -> Vec<String> {
+    providers::known_regions(service_type.as_deref()).iter().map(|region| region.to_string()).collect()
+}
+
+// A warning for a region the provider isn't known to have; None if it's known or the
+// provider accepts any region
+#[tauri::command]
+pub fn validate_s3_region(service_type: Option<String>, region: String) -> Option<String> {
+    providers::region_warning(service_type.as_deref().unwrap_or("Amazon S3"), &region)
+}
+
+#[tauri::command]
+pub fn list_provider_presets() -> Vec<providers::ProviderPreset> {
+    providers::PROVIDER_PRESETS.to_vec()
+}
+
+#[tauri::command]
+pub fn resolve_provider_endpoint(
+    service_type: String,
+    region: String,
+    account_id: Option<String>,
+) -> Result<String, CommandError> {
+    Ok(providers::resolve_endpoint(&service_type, &region, account_id.as_deref())?)
+}
+
+// Sends `count` unsigned GET requests to the endpoint. Any HTTP response means it's
+// reachable; an S3 error document or S3 response headers mean it's an S3 server, which
+// normally answers an anonymous GET / with AccessDenied. Only a malformed endpoint or
+// proxy is an error; connection failures are described in the result.
 #[tauri::command]
 pub async fn ping_endpoint(
     endpoint: String,
-) -> Result<String, String> {
-    println!("Pinging endpoint: {}", endpoint);
+    allow_invalid_certs: Option<bool>,
+    proxy_url: Option<String>,
+    count: Option<u32>,
+) -> Result<EndpointProbe, CommandError> {
+    debug!("Pinging endpoint: {}", endpoint);
     
     // Basic URL validation
     if !endpoint.starts_with("http://") && !endpoint.starts_with("https://") {
-        return Err("Endpoint must start with http:// or https://".to_string());
+        return Err("Endpoint must start with http:// or https://".into());
     }
     
     // Extract host from endpoint
     let url = match url::Url::parse(&endpoint) {
         Ok(u) => u,
-        Err(e) => return Err(format!("Invalid URL format: {}", e)),
+        Err(e) => return Err(format!("Invalid URL format: {}", e).into()),
     };
     
     let host = match url.host_str() {
         Some(h) => h,
-        None => return Err("Could not extract host from URL".to_string()),
+        None => return Err("Could not extract host from URL".into()),
     };
     
     // Try basic HTTP request with timeout
-    let client = reqwest::Client::builder()
+    let allow_invalid_certs = allow_invalid_certs.unwrap_or(false);
+    if allow_invalid_certs {
+        warn!("TLS certificate verification is disabled for ping to {}", host);
+    }
+
+    let mut client_builder = reqwest::Client::builder()
         .timeout(Duration::from_secs(10))
+        .danger_accept_invalid_certs(allow_invalid_certs);
+
+    if let Some(proxy_url) = proxy_url.as_deref().map(str::trim).filter(|url| !url.is_empty()) {
+        crate::http_client::parse_proxy_url(proxy_url)?;
+        let proxy = reqwest::Proxy::all(proxy_url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+        debug!("Pinging {} through proxy", host);
+        client_builder = client_builder.proxy(proxy);
+    }
+
+    let client = client_builder
         .build()
         .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
-    
-    match client.get(&endpoint).send().await {
-        Ok(response) => {
-            let status = response.status();
-            Ok(format!("Endpoint reachable - HTTP {}: {}", status.as_u16(), status.canonical_reason().unwrap_or("Unknown")))
-        },
-        Err(e) => {
-            if e.is_timeout() {
-                Err(format!("Connection timeout to {}", host))
-            } else if e.is_connect() {
-                Err(format!("Connection refused by {}", host))
-            } else if e.to_string().contains("dns") {
-                Err(format!("DNS resolution failed for {}", host))
-            } else {
-                Err(format!("Network error: {}", e))
+
+    let is_https = url.scheme() == "https";
+    let attempts = count.unwrap_or(1).clamp(1, MAX_LATENCY_SAMPLES);
+    let mut durations = Vec::new();
+    let mut answer = None;
+    let mut last_error = None;
+    for _ in 0..attempts {
+        let started = Instant::now();
+        match client.get(&endpoint).send().await {
+            Ok(response) => {
+                durations.push(started.elapsed());
+                debug!("Ping to {} answered with HTTP {}", host, response.status().as_u16());
+                if answer.is_none() {
+                    answer = Some(describe_answer(response).await);
+                }
             }
+            Err(e) => last_error = Some(e),
+        }
+    }
+
+    if let Some((looks_like_s3, detail)) = answer {
+        let stats = LatencyStats::from_samples(&durations, attempts);
+        return Ok(EndpointProbe {
+            reachable: true,
+            looks_like_s3,
+            tls_ok: is_https.then_some(true),
+            latency_ms: Some(stats.avg_ms),
+            detail,
+        });
+    }
+
+    let (tls_ok, detail) = match last_error {
+        Some(e) => describe_request_error(&e, host, is_https),
+        None => (None, format!("No answer from {}", host)),
+    };
+    Ok(EndpointProbe {
+        reachable: false,
+        looks_like_s3: false,
+        tls_ok,
+        latency_ms: None,
+        detail,
+    })
+}
+
+// Error documents are small; anything bigger isn't one
+const MAX_PROBE_BODY_BYTES: usize = 64 * 1024;
+
+// Whether an answer came from an S3 server, and what it said
+async fn describe_answer(mut response: reqwest::Response) -> (bool, String) {
+    let status = response.status().as_u16();
+    let headers = response.headers();
+    let s3_header = ["x-amz-request-id", "x-amz-id-2", "x-minio-deployment-id"]
+        .iter()
+        .any(|name| headers.contains_key(*name));
+    let server = headers
+        .get(reqwest::header::SERVER)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_string();
+
+    let mut body = Vec::new();
+    while let Ok(Some(chunk)) = response.chunk().await {
+        body.extend_from_slice(&chunk);
+        if body.len() > MAX_PROBE_BODY_BYTES {
+            break;
         }
     }
+    let body = String::from_utf8_lossy(&body);
+    let error_code = xml_element(&body, "Code").filter(|_| body.contains("<Error>"));
+    let lists_buckets = body.contains("<ListAllMyBucketsResult");
+
+    let looks_like_s3 = s3_header || error_code.is_some() || lists_buckets || server.eq_ignore_ascii_case("AmazonS3");
+    let detail = match (looks_like_s3, error_code) {
+        (true, Some(code)) => format!("S3 endpoint answered HTTP {} ({})", status, code),
+        (true, None) => format!("S3 endpoint answered HTTP {}", status),
+        (false, _) if server.is_empty() => format!("Server answered HTTP {} but doesn't look like S3", status),
+        (false, _) => format!("Server '{}' answered HTTP {} but doesn't look like S3", server, status),
+    };
+    (looks_like_s3, detail)
+}
+
+// Text of the first <name> element, enough for S3's flat error documents
+fn xml_element<'a>(xml: &'a str, name: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", name))? + name.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", name))? + start;
+    Some(xml[start..end].trim())
+}
+
+// Tells DNS, TLS, refused and timed out connections apart. reqwest only exposes the
+// first two through the messages of its error chain.
+fn describe_request_error(err: &reqwest::Error, host: &str, is_https: bool) -> (Option<bool>, String) {
+    let mut chain = Vec::new();
+    let mut source: Option<&dyn std::error::Error> = Some(err);
+    while let Some(current) = source {
+        chain.push(current.to_string());
+        source = current.source();
+    }
+    let details = chain.join(": ");
+    let lowercase = details.to_lowercase();
+
+    if err.is_timeout() {
+        (None, format!("Connection to {} timed out", host))
+    } else if lowercase.contains("dns") || lowercase.contains("resolve") || lowercase.contains("lookup") {
+        (None, format!("DNS resolution failed for {}: {}", host, details))
+    } else if is_https && (lowercase.contains("certificate") || lowercase.contains("tls") || lowercase.contains("ssl")) {
+        (Some(false), format!("TLS handshake with {} failed: {}", host, details))
+    } else if err.is_connect() {
+        (None, format!("Connection refused by {}", host))
+    } else {
+        (None, details)
+    }
 }
 
 #[tauri::command]
 pub async fn test_s3_connection(
     connection_config: ConnectionConfig,
-) -> Result<bool, String> {
+    settings_state: State<'_, SettingsState>,
+) -> Result<bool, CommandError> {
+    // A test of unsaved edits says nothing about the saved connection of the same name
+    let is_saved = match settings_state.lock().await.as_ref() {
+        Some(manager) => manager
+            .connection(&connection_config.name)
+            .is_some_and(|saved| saved.same_target(&connection_config)),
+        None => false,
+    };
+    let connection_name = connection_config.name.clone();
+    let result = run_connection_test(connection_config).await;
+    if is_saved {
+        let outcome = match &result {
+            Ok(true) => Ok(()),
+            Ok(false) => Err("Connection test failed".to_string()),
+            Err(err) => Err(err.message.clone()),
+        };
+        record_connection_attempt(&settings_state, &connection_name, outcome).await;
+    }
+    result
+}
+
+async fn run_connection_test(connection_config: ConnectionConfig) -> Result<bool, CommandError> {
     // Validate configuration before attempting connection
-    if connection_config.access_key.trim().is_empty() {
-        return Err("Access Key cannot be empty".to_string());
+    let uses_keys = !connection_config.anonymous && !connection_config.use_default_credential_chain;
+    if uses_keys && connection_config.access_key.trim().is_empty() {
+        return Err("Access Key cannot be empty unless the default credential chain is used".into());
     }
     
-    if connection_config.secret_key.trim().is_empty() {
-        return Err("Secret Key cannot be empty".to_string());
+    if uses_keys && connection_config.secret_key.trim().is_empty() {
+        return Err("Secret Key cannot be empty unless the default credential chain is used".into());
     }
-    
-    if connection_config.endpoint.trim().is_empty() {
-        return Err("Endpoint URL cannot be empty".to_string());
+
+    if matches!(&connection_config.session_token, Some(token) if token.trim().is_empty()) {
+        return Err("Session Token cannot be empty when provided".into());
     }
-    
+
+    if matches!(connection_config.expiration, Some(expiration) if expiration <= chrono::Utc::now()) {
+        return Err("Temporary credentials have expired".into());
+    }
+
+    // Custom providers name regions freely, so an unknown one is only worth a warning
+    if let Some(warning) = providers::region_warning(&connection_config.service_type, &connection_config.region) {
+        warn!("{}", warning);
+    }
+
+    // The endpoint is checked as connecting would use it, after an empty one has been
+    // filled in from the service type's preset
+    let s3_config = to_s3_config(connection_config);
+    if s3_config.endpoint.trim().is_empty() {
+        return Err("Endpoint URL cannot be empty".into());
+    }
+
     // Validate endpoint URL format
-    if !connection_config.endpoint.starts_with("http://") && !connection_config.endpoint.starts_with("https://") {
-        return Err("Endpoint URL must start with http:// or https://".to_string());
+    if !s3_config.endpoint.starts_with("http://") && !s3_config.endpoint.starts_with("https://") {
+        return Err("Endpoint URL must start with http:// or https://".into());
     }
     
     // Check for common endpoint mistakes
-    if connection_config.endpoint.contains("amazonaws.com") && connection_config.region.trim().is_empty() {
-        return Err("AWS S3 requires a region to be specified".to_string());
+    if s3_config.endpoint.contains("amazonaws.com") && s3_config.region.trim().is_empty() {
+        return Err("AWS S3 requires a region to be specified".into());
     }
 
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: None,
-    };
-
     match S3Service::new(s3_config).await {
         Ok(service) => {
             match service.test_connection().await {
                 Ok(result) => Ok(result),
                 Err(err) => {
-                    println!("S3 connection test error: {:?}", err);
-                    Err(format!("Connection test failed: {}", err))
+                    error!("S3 connection test error: {:?}", err);
+                    Err(CommandError::with_context("Connection test failed", err))
                 }
             }
         }
         Err(err) => {
-            println!("Failed to create S3 service: {:?}", err);
-            Err(format!("Failed to create S3 service: {}", err))
+            error!("Failed to create S3 service: {:?}", err);
+            Err(CommandError::with_context("Failed to create S3 service", err))
         }
     }
 }
 
+// Times `samples` ListBuckets calls on a fresh, uncached client
+#[tauri::command]
+pub async fn measure_s3_latency(
+    connection_config: ConnectionConfig,
+    samples: Option<u32>,
+) -> Result<LatencyStats, CommandError> {
+    let service = S3Service::new(to_s3_config(connection_config))
+        .await
+        .map_err(|err| CommandError::with_context("Failed to create S3 service", err))?;
+
+    match service.measure_latency(samples.unwrap_or(5)).await {
+        Ok(stats) => Ok(stats),
+        Err(err) => Err(CommandError::with_context("Latency measurement failed", err)),
+    }
+}
+
 #[tauri::command]
 pub async fn connect_to_s3(
     connection_name: String,
     connection_config: ConnectionConfig,
+    settings_state: State<'_, SettingsState>,
     s3_state: State<'_, S3ConnectionState>,
-) -> Result<bool, String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: None,
+) -> Result<bool, CommandError> {
+    let s3_config = to_s3_config(connection_config);
+
+    let result = s3_state.lock().await.get_or_create_connection(&connection_name, s3_config).await;
+    match result {
+        Ok(_) => {
+            record_connection_attempt(&settings_state, &connection_name, Ok(())).await;
+            Ok(true)
+        }
+        Err(err) => {
+            record_connection_attempt(&settings_state, &connection_name, Err(err.to_string())).await;
+            Err(CommandError::with_context("Failed to connect to S3", err))
+        }
+    }
+}
+
+// Payload of the `connected` and `connect-failed` events
+#[derive(Debug, Clone, Serialize)]
+pub struct ConnectionEvent {
+    pub connection_name: String,
+    pub error: Option<CommandError>,
+}
+
+// Connects and checks the credentials with a ListBuckets call without holding up the
+// caller; the outcome arrives as a `connected` or `connect-failed` event
+pub fn connect_in_background(app: AppHandle, connection_config: ConnectionConfig) {
+    tauri::async_runtime::spawn(async move {
+        let connection_name = connection_config.name.clone();
+        let s3_state = app.state::<S3ConnectionState>();
+        let settings_state = app.state::<SettingsState>();
+
+        let service = s3_state
+            .lock()
+            .await
+            .get_or_create_connection(&connection_name, to_s3_config(connection_config))
+            .await;
+        let result = match service {
+            Ok(service) => service.test_connection().await.and_then(|ok| match ok {
+                true => Ok(()),
+                false => Err(S3Error::UnknownError("Connection test failed".to_string())),
+            }),
+            Err(err) => Err(err),
+        };
+
+        let (event, error) = match result {
+            Ok(()) => {
+                info!("Connected to '{}'", connection_name);
+                record_connection_attempt(&settings_state, &connection_name, Ok(())).await;
+                (CONNECTED_EVENT, None)
+            }
+            Err(err) => {
+                warn!("Failed to connect to '{}': {}", connection_name, err);
+                // A later manual connect starts from scratch
+                s3_state.lock().await.remove_connection(&connection_name);
+                record_connection_attempt(&settings_state, &connection_name, Err(err.to_string())).await;
+                (CONNECT_FAILED_EVENT, Some(CommandError::with_context("Failed to connect to S3", err)))
+            }
+        };
+        if let Err(e) = app.emit(event, ConnectionEvent { connection_name, error }) {
+            warn!("Failed to emit {} event: {}", event, e);
+        }
+    });
+}
+
+// Builds a fresh service from the config the cached one was created with, checks it
+// and swaps it in, unless the connection was dropped or replaced in the meantime
+async fn rebuild_connection(s3_state: &S3ConnectionState, connection_name: &str) -> Result<(), S3Error> {
+    let Some(current) = s3_state.lock().await.get_connection(connection_name) else {
+        return Err(S3Error::ConnectionNotFound(connection_name.to_string()));
     };
 
+    let service = Arc::new(S3Service::new(current.config().clone()).await?);
+    if !service.test_connection().await? {
+        return Err(S3Error::UnknownError("Connection test failed".to_string()));
+    }
+
     let manager = s3_state.lock().await;
-    match manager.get_or_create_connection(&connection_name, s3_config).await {
-        Ok(_) => Ok(true),
-        Err(err) => Err(format!("Failed to connect to S3: {}", err)),
+    match manager.get_connection(connection_name) {
+        Some(cached) if Arc::ptr_eq(&cached, &current) => {
+            manager.insert_connection(connection_name, service);
+            Ok(())
+        }
+        Some(_) => Ok(()),
+        None => Err(S3Error::ConnectionNotFound(connection_name.to_string())),
+    }
+}
+
+fn emit_health_event(app: &AppHandle, event: &str, connection_name: &str, attempt: Option<u32>) {
+    let payload = ConnectionHealthEvent { connection_name: connection_name.to_string(), attempt };
+    if let Err(e) = app.emit(event, payload) {
+        warn!("Failed to emit {} event: {}", event, e);
+    }
+}
+
+// Run when a connection's requests keep failing to reach the server. Gives up quietly
+// if the connection is disconnected while waiting.
+pub async fn reconnect_with_backoff(app: &AppHandle, connection_name: &str) {
+    emit_health_event(app, CONNECTION_LOST_EVENT, connection_name, None);
+    let s3_state = app.state::<S3ConnectionState>();
+
+    for attempt in 1..=MAX_RECONNECT_ATTEMPTS {
+        tokio::time::sleep(connection_health::reconnect_delay(attempt)).await;
+        match rebuild_connection(&s3_state, connection_name).await {
+            Ok(()) => {
+                info!("Reconnected '{}' after {} attempt(s)", connection_name, attempt);
+                emit_health_event(app, CONNECTION_RESTORED_EVENT, connection_name, Some(attempt));
+                return;
+            }
+            Err(S3Error::ConnectionNotFound(_)) => return,
+            Err(err) => debug!("Reconnect attempt {} for '{}' failed: {}", attempt, connection_name, err),
+        }
     }
+    warn!("Gave up reconnecting '{}' after {} attempts", connection_name, MAX_RECONNECT_ATTEMPTS);
+}
+
+// Rebuilds a cached connection right away, e.g. from a "Retry" button after the
+// automatic reconnects gave up
+#[tauri::command]
+pub async fn reconnect_s3(
+    connection_name: String,
+    app: AppHandle,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    match rebuild_connection(&s3_state, &connection_name).await {
+        Ok(()) => {
+            emit_health_event(&app, CONNECTION_RESTORED_EVENT, &connection_name, None);
+            Ok(())
+        }
+        Err(err) => Err(CommandError::with_context("Failed to reconnect", err)),
+    }
+}
+
+async fn record_connection_attempt(settings_state: &SettingsState, connection_name: &str, result: Result<(), String>) {
+    if let Some(manager) = settings_state.lock().await.as_mut() {
+        manager.record_connection_attempt(connection_name, result);
+    }
+}
+
+// Drops the cached client for a connection and stops everything polling through it.
+// Also used when a saved connection is removed, so its old credentials can't be reused.
+pub async fn disconnect_connection(s3_state: &S3ConnectionState, watches: &PrefixWatchState, connection_name: &str) {
+    s3_state.lock().await.remove_connection(connection_name);
+    auto_refresh::clear_connection(connection_name);
+    watches.unwatch_connection(connection_name);
 }
 
 #[tauri::command]
 pub async fn disconnect_from_s3(
     connection_name: String,
+    watches: State<'_, PrefixWatchState>,
     s3_state: State<'_, S3ConnectionState>,
-) -> Result<(), String> {
-    let manager = s3_state.lock().await;
-    manager.remove_connection(&connection_name);
+) -> Result<(), CommandError> {
+    disconnect_connection(&s3_state, &watches, &connection_name).await;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn disconnect_all_s3(
+    watches: State<'_, PrefixWatchState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    s3_state.lock().await.clear_connections();
+    auto_refresh::set_active_view(None);
+    watches.unwatch_all();
     Ok(())
 }
 
 #[tauri::command]
 pub async fn list_s3_buckets(
-    _connection_name: String,
-    _s3_state: State<'_, S3ConnectionState>,
-) -> Result<Vec<BucketInfo>, String> {
-    // We need the connection config to create the service
-    // For now, we'll return an error asking for reconnection
-    Err("Please reconnect to S3 to list buckets".to_string())
+    connection_name: String,
+    include_regions: Option<bool>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Vec<BucketInfo>, CommandError> {
+    let service = get_service(&s3_state, &connection_name, None).await?;
+
+    match service.list_buckets(include_regions.unwrap_or(false)).await {
+        Ok(buckets) => Ok(buckets),
+        Err(err) => Err(CommandError::with_context("Failed to list buckets", err)),
+    }
 }
 
 #[tauri::command]
 pub async fn list_s3_buckets_with_config(
     connection_config: ConnectionConfig,
-) -> Result<Vec<BucketInfo>, String> {
+    include_regions: Option<bool>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Vec<BucketInfo>, CommandError> {
     // Validate configuration
-    if connection_config.access_key.trim().is_empty() || connection_config.secret_key.trim().is_empty() {
-        return Err("Invalid credentials: Access Key and Secret Key are required".to_string());
+    let missing_keys = connection_config.access_key.trim().is_empty() || connection_config.secret_key.trim().is_empty();
+    if !connection_config.anonymous && !connection_config.use_default_credential_chain && missing_keys {
+        return Err("Invalid credentials: Access Key and Secret Key are required".into());
     }
     
     if connection_config.endpoint.trim().is_empty() {
-        return Err("Endpoint URL is required".to_string());
+        return Err("Endpoint URL is required".into());
     }
 
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint.clone(),
-        access_key: connection_config.access_key.clone(),
-        secret_key: connection_config.secret_key.clone(),
-        region: connection_config.region.clone(),
-        bucket: None,
-    };
+    let s3_config = to_s3_config(connection_config.clone());
 
-    println!("Attempting to list buckets for endpoint: {}", connection_config.endpoint);
+    debug!("Attempting to list buckets for endpoint: {}", connection_config.endpoint);
 
     match S3Service::new(s3_config).await {
         Ok(service) => {
-            match service.list_buckets().await {
+            // Cache the freshly built service so later commands can reuse it by name
+            let service = Arc::new(service);
+            s3_state.lock().await.insert_connection(&connection_config.name, Arc::clone(&service));
+
+            match service.list_buckets(include_regions.unwrap_or(false)).await {
                 Ok(buckets) => {
-                    println!("Successfully listed {} buckets", buckets.len());
+                    info!("Successfully listed {} buckets", buckets.len());
                     Ok(buckets)
                 },
                 Err(err) => {
-                    println!("Failed to list buckets: {:?}", err);
+                    error!("Failed to list buckets: {:?}", err);
                     
                     // Provide helpful error messages based on error type
-                    let error_message = match err.to_string().as_str() {
-                        s if s.contains("InvalidAccessKeyId") => "Invalid Access Key ID - please check your credentials".to_string(),
-                        s if s.contains("SignatureDoesNotMatch") => "Invalid Secret Key - signature mismatch".to_string(),
-                        s if s.contains("AccessDenied") => "Access denied - check your permissions".to_string(),
-                        s if s.contains("dns") || s.contains("resolve") => format!("Cannot resolve endpoint '{}' - check your endpoint URL", connection_config.endpoint),
-                        s if s.contains("connection") || s.contains("timeout") => format!("Connection failed to '{}' - check network connectivity", connection_config.endpoint),
-                        _ => format!("Failed to list buckets: {}", err)
+                    let message = match &err {
+                        S3Error::InvalidCredentials => "Invalid credentials - please check your access key and secret key".to_string(),
+                        S3Error::PermissionDenied | S3Error::RequesterPaysRequired => "Access denied - check your permissions".to_string(),
+                        S3Error::NetworkError(msg) => format!("Connection failed to '{}' - {}", connection_config.endpoint, msg),
+                        _ => return Err(CommandError::with_context("Failed to list buckets", err)),
                     };
-                    
-                    Err(error_message)
+
+                    Err(CommandError { message, ..CommandError::from(err) })
                 }
             }
         }
         Err(err) => {
-            println!("Failed to create S3 service for list_buckets: {:?}", err);
+            error!("Failed to create S3 service for list_buckets: {:?}", err);
             
-            let error_message = if err.to_string().contains("Invalid URI") {
-                format!("Invalid endpoint URL format: '{}'. Please use format like 'https://s3.amazonaws.com' or 'http://localhost:9000'", connection_config.endpoint)
-            } else {
-                format!("Failed to create S3 service: {}", err)
-            };
-            
-            Err(error_message)
+            if err.to_string().contains("Invalid URI") {
+                return Err(CommandError::new(
+                    ErrorCode::ConfigurationError,
+                    format!("Invalid endpoint URL format: '{}'. Please use format like 'https://s3.amazonaws.com' or 'http://localhost:9000'", connection_config.endpoint),
+                ));
+            }
+
+            Err(CommandError::with_context("Failed to create S3 service", err))
         }
     }
 }
 
 #[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn list_s3_objects(
-    connection_config: ConnectionConfig,
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
     bucket: String,
     prefix: Option<String>,
     delimiter: Option<String>,
     max_keys: Option<i32>,
     continuation_token: Option<String>,
-) -> Result<ListObjectsResponse, String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: Some(bucket.clone()),
+    sort_by: Option<SortField>,
+    sort_direction: Option<SortDirection>,
+    pattern: Option<String>,
+    refresh: Option<bool>,
+    // Costs one HeadObject per listed object, see S3Service::enrich_objects
+    enrich: Option<bool>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<ListObjectsResponse, CommandError> {
+    let pattern = match pattern.as_deref().filter(|p| !p.is_empty()) {
+        Some(p) => Some(KeyPattern::new(p).map_err(|err| err.to_string())?),
+        None => None,
+    };
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let cache_ttl = listing_cache_ttl(&settings_state).await;
+    let max_keys = match max_keys {
+        Some(max_keys) => Some(max_keys),
+        None => Some(default_page_size(&settings_state).await),
     };
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.list_objects(
-                &bucket,
-                prefix.as_deref(),
-                delimiter.as_deref(),
-                max_keys,
-                continuation_token.as_deref(),
-            ).await {
-                Ok(response) => Ok(response),
-                Err(err) => {
-                    println!("Failed to list objects in bucket '{}': {:?}", bucket, err);
-                    Err(format!("Failed to list objects: {}", err))
-                }
+    let result = match cache_ttl {
+        Some(ttl) => {
+            service
+                .list_objects_cached(
+                    &bucket,
+                    prefix.as_deref(),
+                    delimiter.as_deref(),
+                    max_keys,
+                    continuation_token.as_deref(),
+                    ttl,
+                    refresh.unwrap_or(false),
+                )
+                .await
+        }
+        None => {
+            service
+                .list_objects(&bucket, prefix.as_deref(), delimiter.as_deref(), max_keys, continuation_token.as_deref())
+                .await
+        }
+    };
+
+    match result {
+        Ok(mut response) => {
+            if let Some(pattern) = &pattern {
+                let listed_prefix = prefix.as_deref().unwrap_or_default();
+                response.objects.retain(|object| pattern.matches(listed_prefix, &object.key));
+            }
+            if enrich.unwrap_or(false) {
+                service.enrich_objects(&bucket, &mut response.objects).await;
             }
+            if let Some(sort_by) = sort_by {
+                response.sort(sort_by, sort_direction.unwrap_or_default());
+            }
+            Ok(response)
         }
         Err(err) => {
-            println!("Failed to create S3 service for list_objects: {:?}", err);
-            Err(format!("Failed to create S3 service: {}", err))
+            error!("Failed to list objects in bucket '{}': {:?}", bucket, err);
+            Err(CommandError::with_context("Failed to list objects", err))
         }
     }
 }
 
+// Writes made through this app invalidate the cache themselves; this is for changes
+// it can't see, such as uploads through a presigned URL
 #[tauri::command]
-pub async fn get_s3_object_info(
-    connection_config: ConnectionConfig,
+pub async fn invalidate_s3_cache(
+    connection_name: String,
     bucket: String,
-    key: String,
-) -> Result<ObjectInfo, String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: Some(bucket.clone()),
-    };
-
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.get_object_info(&bucket, &key).await {
-                Ok(info) => Ok(info),
-                Err(err) => Err(format!("Failed to get object info: {}", err)),
-            }
-        }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
+    prefix: Option<String>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    if let Some(service) = s3_state.lock().await.get_connection(&connection_name) {
+        service.invalidate_listings(&bucket, prefix.as_deref());
     }
+    Ok(())
 }
 
 #[tauri::command]
-pub async fn delete_s3_object(
-    connection_config: ConnectionConfig,
+pub async fn clear_s3_cache(s3_state: State<'_, S3ConnectionState>) -> Result<(), CommandError> {
+    s3_state.lock().await.clear_listing_caches();
+    Ok(())
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn list_all_s3_objects(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
     bucket: String,
-    key: String,
-) -> Result<(), String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: Some(bucket.clone()),
-    };
+    prefix: Option<String>,
+    max_total: Option<usize>,
+    sort_by: Option<SortField>,
+    sort_direction: Option<SortDirection>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<ListAllObjectsResponse, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.delete_object(&bucket, &key).await {
-                Ok(_) => Ok(()),
-                Err(err) => Err(format!("Failed to delete object: {}", err)),
+    match service.list_all_objects(&bucket, prefix.as_deref(), max_total).await {
+        Ok(mut response) => {
+            if let Some(sort_by) = sort_by {
+                sort_objects(&mut response.objects, sort_by, sort_direction.unwrap_or_default());
             }
+            Ok(response)
+        }
+        Err(err) => {
+            error!("Failed to list all objects in bucket '{}': {:?}", bucket, err);
+            Err(CommandError::with_context("Failed to list objects", err))
         }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
     }
 }
 
 #[tauri::command]
-pub async fn delete_s3_objects(
-    connection_config: ConnectionConfig,
+pub async fn get_s3_prefix_stats(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
     bucket: String,
-    keys: Vec<String>,
-) -> Result<Vec<String>, String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: Some(bucket.clone()),
+    prefix: Option<String>,
+    window: Window,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<PrefixStats, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let on_page = |stats: &PrefixStats| {
+        if let Err(e) = window.emit(PREFIX_STATS_PROGRESS_EVENT, stats.clone()) {
+            warn!("Failed to emit prefix stats progress: {}", e);
+        }
     };
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.delete_objects(&bucket, keys).await {
-                Ok(failed_keys) => Ok(failed_keys),
-                Err(err) => Err(format!("Failed to delete objects: {}", err)),
-            }
-        }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
+    match service.get_prefix_stats(&bucket, prefix.as_deref(), &on_page).await {
+        Ok(stats) => Ok(stats),
+        Err(err) => Err(CommandError::with_context("Failed to compute prefix stats", err)),
     }
 }
 
+// Polls the prefix and emits objects-added/removed/changed events until unwatched.
+// Returns the watch id.
 #[tauri::command]
-pub async fn create_s3_bucket(
-    connection_config: ConnectionConfig,
+#[allow(clippy::too_many_arguments)]
+pub async fn watch_s3_prefix(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
     bucket: String,
-    region: Option<String>,
-) -> Result<(), String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region.clone(),
-        bucket: None,
-    };
-
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.create_bucket(&bucket, region.as_deref()).await {
-                Ok(_) => Ok(()),
-                Err(err) => Err(format!("Failed to create bucket: {}", err)),
-            }
-        }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
+    prefix: Option<String>,
+    interval_secs: u64,
+    window: Window,
+    watches: State<'_, PrefixWatchState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<String, CommandError> {
+    if interval_secs < MIN_WATCH_INTERVAL_SECS {
+        return Err(CommandError::new(
+            ErrorCode::ConfigurationError,
+            format!("Watch interval must be at least {} seconds", MIN_WATCH_INTERVAL_SECS),
+        ));
     }
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    Ok(watches.watch(
+        window,
+        service,
+        &connection_name,
+        bucket,
+        prefix.unwrap_or_default(),
+        Duration::from_secs(interval_secs),
+    ))
 }
 
 #[tauri::command]
-pub async fn delete_s3_bucket(
-    connection_config: ConnectionConfig,
+pub async fn unwatch_s3_prefix(
+    watch_id: String,
+    watches: State<'_, PrefixWatchState>,
+) -> Result<bool, CommandError> {
+    Ok(watches.unwatch(&watch_id))
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn search_s3_objects(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    search_id: String,
     bucket: String,
-) -> Result<(), String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: None,
+    prefix: Option<String>,
+    query: String,
+    case_sensitive: Option<bool>,
+    max_results: Option<usize>,
+    window: Window,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<SearchObjectsResponse, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let on_page = |matches: &[ObjectInfo], scanned: u64| {
+        let progress = SearchProgress {
+            id: search_id.clone(),
+            matches: matches.to_vec(),
+            scanned,
+        };
+        if let Err(e) = window.emit(SEARCH_PROGRESS_EVENT, progress) {
+            warn!("Failed to emit search progress for {}: {}", search_id, e);
+        }
     };
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.delete_bucket(&bucket).await {
-                Ok(_) => Ok(()),
-                Err(err) => Err(format!("Failed to delete bucket: {}", err)),
-            }
-        }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
+    match service
+        .search_objects(&bucket, prefix.as_deref(), &query, case_sensitive.unwrap_or(false), max_results, &on_page)
+        .await
+    {
+        Ok(response) => Ok(response),
+        Err(err) => Err(CommandError::with_context("Failed to search objects", err)),
     }
 }
 
 #[tauri::command]
-pub async fn create_s3_folder(
-    connection_config: ConnectionConfig,
+pub async fn get_s3_object_info(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
     bucket: String,
-    folder_path: String,
-) -> Result<(), String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: Some(bucket.clone()),
-    };
+    key: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<ObjectInfo, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.create_folder(&bucket, &folder_path).await {
-                Ok(_) => Ok(()),
-                Err(err) => Err(format!("Failed to create folder: {}", err)),
-            }
+    match service.get_object_info(&bucket, &key).await {
+        Ok(info) => Ok(info),
+        Err(err) => Err(CommandError::with_context("Failed to get object info", err)),
+    }
+}
+
+// Size and modification time as the UI shows them, in the units chosen in settings
+#[tauri::command]
+pub async fn format_object_info(
+    object: ObjectInfo,
+    settings_state: State<'_, SettingsState>,
+) -> Result<FormattedObjectInfo, CommandError> {
+    let units = settings_state
+        .lock()
+        .await
+        .as_ref()
+        .map(|manager| manager.general_settings().size_units)
+        .unwrap_or_default();
+
+    Ok(format::format_object_info(object.size, object.last_modified.as_deref(), units))
+}
+
+// For objects stored without a useful content type, e.g. to pick a previewer
+#[tauri::command]
+pub async fn detect_s3_object_content_type(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Option<String>, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.detect_content_type(&bucket, &key).await {
+        Ok(content_type) => Ok(content_type),
+        Err(err) => Err(CommandError::with_context("Failed to detect content type", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_object_range(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    start: u64,
+    end: u64,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<ObjectRangeResponse, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_object_range(&bucket, &key, start, end).await {
+        Ok(range) => Ok(range),
+        Err(err) => Err(CommandError::with_context("Failed to read object range", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn read_s3_text_object(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    max_bytes: Option<u64>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<TextObjectResponse, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let max_bytes = max_bytes.unwrap_or(DEFAULT_TEXT_PREVIEW_BYTES);
+
+    match service.read_text_object(&bucket, &key, max_bytes).await {
+        Ok(text) => Ok(text),
+        Err(err) => Err(CommandError::with_context("Failed to read text object", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_image_thumbnail(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    max_dimension: Option<u32>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<ImageThumbnail, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let max_dimension = max_dimension.unwrap_or(DEFAULT_THUMBNAIL_DIMENSION);
+
+    match service.get_image_thumbnail(&bucket, &key, max_dimension).await {
+        Ok(thumbnail) => Ok(thumbnail),
+        Err(err) => Err(CommandError::with_context("Failed to create thumbnail", err)),
+    }
+}
+
+// With a query id the records are emitted as they arrive instead of being returned,
+// which is the only way to get results over the size limit
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn s3_select_query(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    expression: String,
+    input_format: Option<SelectInput>,
+    output_format: Option<SelectOutputFormat>,
+    query_id: Option<String>,
+    window: Window,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<SelectResult, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let input = input_format.unwrap_or_default();
+
+    let emit_records = |records: String| {
+        let Some(id) = &query_id else { return };
+        let event = SelectRecords { id: id.clone(), records };
+        if let Err(e) = window.emit(SELECT_RECORDS_EVENT, event) {
+            warn!("Failed to emit select records: {}", e);
         }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
+    };
+    let on_records: Option<&(dyn Fn(String) + Send + Sync)> = match query_id {
+        Some(_) => Some(&emit_records),
+        None => None,
+    };
+
+    match service
+        .select_object_content(&bucket, &key, &expression, &input, output_format.unwrap_or_default(), on_records)
+        .await
+    {
+        Ok(result) => Ok(result),
+        Err(err) => Err(CommandError::with_context("Failed to run S3 Select query", err)),
     }
 }
 
 #[tauri::command]
-pub async fn generate_s3_download_url(
-    connection_config: ConnectionConfig,
+#[allow(clippy::too_many_arguments)]
+pub async fn restore_s3_object(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
     bucket: String,
     key: String,
-    expires_in_secs: u64,
-) -> Result<PresignedUrlResponse, String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: Some(bucket.clone()),
+    days: i32,
+    tier: RestoreTier,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.restore_object(&bucket, &key, days, tier).await {
+        Ok(()) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to restore object", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn download_s3_object(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    transfer_id: String,
+    bucket: String,
+    key: String,
+    dest_path: String,
+    overwrite: Option<bool>,
+    verify_checksum: Option<bool>,
+    window: Window,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+    cancellations: State<'_, CancellationRegistry>,
+    scheduler: State<'_, TransferScheduler>,
+    bandwidth: State<'_, BandwidthState>,
+) -> Result<u64, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let path = PathBuf::from(dest_path);
+    let cancel_token = cancellations.register(&transfer_id);
+    let _permit = match acquire_transfer_slot(&settings_state, &scheduler, &cancel_token).await {
+        Ok(permit) => permit,
+        Err(err) => {
+            cancellations.remove(&transfer_id);
+            return Err(err);
+        }
+    };
+    let reporter = ProgressReporter::new(window, transfer_id.clone(), key.clone());
+    let on_progress = |transferred, total| reporter.update(transferred, total);
+    let transfer = TransferContext {
+        on_progress: &on_progress,
+        cancel_token: &cancel_token,
+        bandwidth: &bandwidth,
     };
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.generate_presigned_download_url(&bucket, &key, expires_in_secs).await {
-                Ok(response) => Ok(response),
-                Err(err) => Err(format!("Failed to generate download URL: {}", err)),
-            }
+    let result = service
+        .download_object(&bucket, &key, &path, overwrite.unwrap_or(false), verify_checksum.unwrap_or(true), &transfer)
+        .await;
+    cancellations.remove(&transfer_id);
+
+    match result {
+        Ok(bytes_written) => {
+            reporter.complete();
+            Ok(bytes_written)
+        }
+        Err(err) => {
+            let error = CommandError::with_context("Failed to download object", err);
+            reporter.error(&error.message);
+            Err(error)
         }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
     }
 }
 
 #[tauri::command]
-pub async fn generate_s3_upload_url(
-    connection_config: ConnectionConfig,
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_s3_object(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    transfer_id: String,
     bucket: String,
     key: String,
-    expires_in_secs: u64,
+    source_path: String,
     content_type: Option<String>,
-) -> Result<PresignedUrlResponse, String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: Some(bucket.clone()),
+    multipart_threshold: Option<u64>,
+    sse: Option<SseAlgorithm>,
+    kms_key_id: Option<String>,
+    checksum_algorithm: Option<ChecksumAlgorithm>,
+    // ETag conditions, e.g. if_none_match "*" to never overwrite an existing key
+    if_match: Option<String>,
+    if_none_match: Option<String>,
+    window: Window,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+    cancellations: State<'_, CancellationRegistry>,
+    scheduler: State<'_, TransferScheduler>,
+    bandwidth: State<'_, BandwidthState>,
+) -> Result<UploadResult, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let encryption = ObjectEncryption::new(sse, kms_key_id)?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let path = PathBuf::from(source_path);
+    let threshold = multipart_threshold.unwrap_or(DEFAULT_MULTIPART_THRESHOLD);
+    let preconditions = Preconditions { if_match, if_none_match };
+    let cancel_token = cancellations.register(&transfer_id);
+    let _permit = match acquire_transfer_slot(&settings_state, &scheduler, &cancel_token).await {
+        Ok(permit) => permit,
+        Err(err) => {
+            cancellations.remove(&transfer_id);
+            return Err(err);
+        }
+    };
+    let reporter = ProgressReporter::new(window, transfer_id.clone(), key.clone());
+    let on_progress = |transferred, total| reporter.update(transferred, total);
+    let transfer = TransferContext {
+        on_progress: &on_progress,
+        cancel_token: &cancel_token,
+        bandwidth: &bandwidth,
     };
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.generate_presigned_upload_url(&bucket, &key, expires_in_secs, content_type.as_deref()).await {
-                Ok(response) => Ok(response),
-                Err(err) => Err(format!("Failed to generate upload URL: {}", err)),
-            }
+    let result = service
+        .upload_object(
+            &bucket,
+            &key,
+            &path,
+            content_type.as_deref(),
+            threshold,
+            encryption.as_ref(),
+            checksum_algorithm.unwrap_or_default(),
+            &preconditions,
+            &transfer,
+        )
+        .await;
+    cancellations.remove(&transfer_id);
+
+    match result {
+        Ok(upload) => {
+            reporter.complete();
+            Ok(upload)
+        }
+        Err(err) => {
+            let error = CommandError::with_context("Failed to upload object", err);
+            reporter.error(&error.message);
+            Err(error)
         }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
     }
 }
 
+// Downloads everything under a prefix into a local directory; like a directory upload
+// it takes one transfer slot for the whole download
 #[tauri::command]
-pub async fn copy_s3_object(
-    connection_config: ConnectionConfig,
-    source_bucket: String,
-    source_key: String,
-    dest_bucket: String,
-    dest_key: String,
-) -> Result<(), String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: None,
+#[allow(clippy::too_many_arguments)]
+pub async fn download_s3_directory(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    transfer_id: String,
+    bucket: String,
+    prefix: String,
+    dest_dir: String,
+    skip_existing: Option<bool>,
+    max_concurrency: Option<usize>,
+    window: Window,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+    cancellations: State<'_, CancellationRegistry>,
+    scheduler: State<'_, TransferScheduler>,
+    bandwidth: State<'_, BandwidthState>,
+) -> Result<DirectoryDownloadResult, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let path = PathBuf::from(&dest_dir);
+    let cancel_token = cancellations.register(&transfer_id);
+    let _permit = match acquire_transfer_slot(&settings_state, &scheduler, &cancel_token).await {
+        Ok(permit) => permit,
+        Err(err) => {
+            cancellations.remove(&transfer_id);
+            return Err(err);
+        }
+    };
+    let reporter = ProgressReporter::new(window, transfer_id.clone(), prefix.clone());
+    let on_progress = |transferred, total| reporter.update(transferred, total);
+    let transfer = TransferContext {
+        on_progress: &on_progress,
+        cancel_token: &cancel_token,
+        bandwidth: &bandwidth,
     };
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.copy_object(&source_bucket, &source_key, &dest_bucket, &dest_key).await {
-                Ok(_) => Ok(()),
-                Err(err) => Err(format!("Failed to copy object: {}", err)),
-            }
+    let result = service
+        .download_directory(
+            &bucket,
+            &prefix,
+            &path,
+            skip_existing.unwrap_or(false),
+            max_concurrency.unwrap_or(DEFAULT_DOWNLOAD_CONCURRENCY),
+            &transfer,
+        )
+        .await;
+    cancellations.remove(&transfer_id);
+
+    match result {
+        Ok(result) => {
+            info!(
+                "Downloaded {}/{} to {}: {} downloaded, {} skipped, {} failed",
+                bucket,
+                prefix,
+                dest_dir,
+                result.downloaded.len(),
+                result.skipped.len(),
+                result.failed.len()
+            );
+            reporter.complete();
+            Ok(result)
+        }
+        Err(err) => {
+            let error = CommandError::with_context("Failed to download folder", err);
+            reporter.error(&error.message);
+            Err(error)
         }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
     }
 }
 
+// Uploads a local directory tree; the whole upload takes one transfer slot and runs up
+// to `max_concurrency` files at once within it
 #[tauri::command]
-pub async fn get_s3_bucket_location(
-    connection_config: ConnectionConfig,
+#[allow(clippy::too_many_arguments)]
+pub async fn upload_s3_directory(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    transfer_id: String,
     bucket: String,
-) -> Result<String, String> {
-    let s3_config = S3Config {
-        endpoint: connection_config.endpoint,
-        access_key: connection_config.access_key,
-        secret_key: connection_config.secret_key,
-        region: connection_config.region,
-        bucket: Some(bucket.clone()),
+    dest_prefix: String,
+    source_dir: String,
+    include_hidden: Option<bool>,
+    max_concurrency: Option<usize>,
+    window: Window,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+    cancellations: State<'_, CancellationRegistry>,
+    scheduler: State<'_, TransferScheduler>,
+    bandwidth: State<'_, BandwidthState>,
+) -> Result<BatchResult, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let path = PathBuf::from(&source_dir);
+    let cancel_token = cancellations.register(&transfer_id);
+    let _permit = match acquire_transfer_slot(&settings_state, &scheduler, &cancel_token).await {
+        Ok(permit) => permit,
+        Err(err) => {
+            cancellations.remove(&transfer_id);
+            return Err(err);
+        }
+    };
+    let reporter = ProgressReporter::new(window, transfer_id.clone(), source_dir.clone());
+    let on_progress = |transferred, total| reporter.update(transferred, total);
+    let transfer = TransferContext {
+        on_progress: &on_progress,
+        cancel_token: &cancel_token,
+        bandwidth: &bandwidth,
     };
 
-    match S3Service::new(s3_config).await {
-        Ok(service) => {
-            match service.get_bucket_location(&bucket).await {
-                Ok(location) => Ok(location),
-                Err(err) => Err(format!("Failed to get bucket location: {}", err)),
+    let result = service
+        .upload_directory(
+            &bucket,
+            &dest_prefix,
+            &path,
+            include_hidden.unwrap_or(false),
+            max_concurrency.unwrap_or(DEFAULT_UPLOAD_CONCURRENCY),
+            &transfer,
+        )
+        .await;
+    cancellations.remove(&transfer_id);
+
+    match result {
+        Ok(result) => {
+            info!("Uploaded {} files from {} ({} failed)", result.succeeded.len(), source_dir, result.failed.len());
+            reporter.complete();
+            Ok(result)
+        }
+        Err(err) => {
+            let error = CommandError::with_context("Failed to upload directory", err);
+            reporter.error(&error.message);
+            Err(error)
+        }
+    }
+}
+
+// Brings the scheduler in line with the saved limit, then waits for a transfer slot
+async fn acquire_transfer_slot(
+    settings_state: &SettingsState,
+    scheduler: &TransferScheduler,
+    cancel_token: &CancellationToken,
+) -> Result<TransferPermit, CommandError> {
+    if let Some(manager) = settings_state.lock().await.as_ref() {
+        scheduler.set_limit(manager.general_settings().max_concurrent_transfers);
+    }
+    scheduler.acquire(cancel_token).await.ok_or_else(|| S3Error::Cancelled.into())
+}
+
+#[tauri::command]
+pub async fn set_max_concurrent_transfers(
+    max_concurrent_transfers: u32,
+    settings_state: State<'_, SettingsState>,
+    scheduler: State<'_, TransferScheduler>,
+) -> Result<AppSettings, CommandError> {
+    let mut settings_guard = settings_state.lock().await;
+    let manager = settings_guard.as_mut().ok_or("Settings manager not initialized")?;
+    let general = GeneralSettings {
+        max_concurrent_transfers,
+        ..manager.general_settings().clone()
+    };
+    let settings = manager
+        .update_general_settings(general)
+        .await
+        .map_err(|e| CommandError::with_context("Failed to update transfer limit", e))?;
+    scheduler.set_limit(max_concurrent_transfers);
+    Ok(settings)
+}
+
+// Cancels an ad-hoc transfer or a queued job. A queued job that isn't running is
+// cleaned up here; a running one cleans up after itself once it stops.
+#[tauri::command]
+pub async fn cancel_transfer(
+    transfer_id: String,
+    window: Window,
+    s3_state: State<'_, S3ConnectionState>,
+    cancellations: State<'_, CancellationRegistry>,
+    queue: State<'_, TransferQueueState>,
+) -> Result<bool, CommandError> {
+    if cancellations.cancel(&transfer_id) {
+        return Ok(true);
+    }
+
+    match queue.cancel(&transfer_id) {
+        Some((job, true)) => {
+            emit_status(&window, &job);
+            Ok(true)
+        }
+        Some((job, false)) => {
+            let job = match get_service(&s3_state, &job.connection_name, None).await {
+                Ok(service) => discard_partial_transfer(&service, &queue, job).await,
+                Err(_) => job,
+            };
+            emit_status(&window, &job);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn enqueue_transfer(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    kind: TransferKind,
+    bucket: String,
+    key: String,
+    local_path: String,
+    overwrite: Option<bool>,
+    window: Window,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+    queue: State<'_, TransferQueueState>,
+) -> Result<TransferJob, CommandError> {
+    if kind == TransferKind::Upload {
+        ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    }
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    let job = TransferJob::new(kind, connection_name, bucket, key, local_path, overwrite.unwrap_or(false));
+    let cancel_token = queue.enqueue(job.clone());
+    info!("Queued {:?} of {}/{} as {}", kind, job.bucket, job.key, job.id);
+    emit_status(&window, &job);
+    spawn_queued_transfer(window, Arc::clone(&queue), service, job.clone(), cancel_token);
+    Ok(job)
+}
+
+#[tauri::command]
+pub async fn pause_transfer(
+    transfer_id: String,
+    window: Window,
+    queue: State<'_, TransferQueueState>,
+) -> Result<TransferJob, CommandError> {
+    let job = queue
+        .pause(&transfer_id)
+        .ok_or_else(|| format!("Transfer {} is not queued or running", transfer_id))?;
+    emit_status(&window, &job);
+    Ok(job)
+}
+
+// Continues a paused or failed job from where it stopped
+#[tauri::command]
+pub async fn resume_transfer(
+    transfer_id: String,
+    connection_config: Option<ConnectionConfig>,
+    window: Window,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+    queue: State<'_, TransferQueueState>,
+) -> Result<TransferJob, CommandError> {
+    restart_transfer(
+        &transfer_id,
+        connection_config,
+        &[TransferStatus::Paused, TransferStatus::Failed],
+        false,
+        window,
+        &settings_state,
+        &s3_state,
+        &queue,
+    )
+    .await
+}
+
+// Runs a failed or cancelled job again from the start
+#[tauri::command]
+pub async fn retry_transfer(
+    transfer_id: String,
+    connection_config: Option<ConnectionConfig>,
+    window: Window,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+    queue: State<'_, TransferQueueState>,
+) -> Result<TransferJob, CommandError> {
+    restart_transfer(
+        &transfer_id,
+        connection_config,
+        &[TransferStatus::Failed, TransferStatus::Cancelled],
+        true,
+        window,
+        &settings_state,
+        &s3_state,
+        &queue,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn list_transfers(queue: State<'_, TransferQueueState>) -> Result<Vec<TransferJob>, CommandError> {
+    Ok(queue.list())
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn restart_transfer(
+    transfer_id: &str,
+    connection_config: Option<ConnectionConfig>,
+    from: &[TransferStatus],
+    fresh_start: bool,
+    window: Window,
+    settings_state: &SettingsState,
+    s3_state: &S3ConnectionState,
+    queue: &TransferQueueState,
+) -> Result<TransferJob, CommandError> {
+    let job = queue
+        .get(transfer_id)
+        .ok_or_else(|| format!("Transfer {} not found", transfer_id))?;
+    if job.kind == TransferKind::Upload {
+        ensure_writable(settings_state, &job.connection_name, connection_config.as_ref()).await?;
+    }
+    let service = get_service(s3_state, &job.connection_name, connection_config).await?;
+
+    // Starting over leaves the previous attempt's parts or partial file behind otherwise
+    if fresh_start {
+        match (&job.kind, &job.upload_id) {
+            (TransferKind::Upload, Some(upload_id)) => {
+                if let Err(e) = service.abort_multipart_upload(&job.bucket, &job.key, upload_id).await {
+                    warn!("Failed to abort multipart upload {} of transfer {}: {}", upload_id, job.id, e);
+                }
+            }
+            (TransferKind::Download, _) => discard_partial_download(Path::new(&job.local_path)).await,
+            _ => {}
+        }
+    }
+
+    let (job, cancel_token) = queue.requeue(transfer_id, from, fresh_start)?;
+    emit_status(&window, &job);
+    spawn_queued_transfer(window, Arc::clone(queue), service, job.clone(), cancel_token);
+    Ok(job)
+}
+
+// Waits for a transfer slot, runs the job and records the outcome
+fn spawn_queued_transfer(
+    window: Window,
+    queue: TransferQueueState,
+    service: Arc<S3Service>,
+    job: TransferJob,
+    cancel_token: CancellationToken,
+) {
+    tauri::async_runtime::spawn(async move {
+        let app = window.app_handle().clone();
+        let settings_state = app.state::<SettingsState>();
+        let scheduler = app.state::<TransferScheduler>();
+
+        let result = match acquire_transfer_slot(&settings_state, &scheduler, &cancel_token).await {
+            Ok(_permit) => match queue.start(&job.id) {
+                Some(job) => {
+                    emit_status(&window, &job);
+                    run_queued_transfer(&window, &queue, &service, &job, &cancel_token)
+                        .await
+                        .map_err(|err| CommandError::with_context("Transfer failed", err))
+                }
+                None => Err(S3Error::Cancelled.into()),
+            },
+            Err(err) => Err(err),
+        };
+        if let Err(err) = &result {
+            debug!("Transfer {} stopped: {}", job.id, err.message);
+        }
+
+        let Some(mut finished) = queue.finish(&job.id, result) else {
+            return;
+        };
+        if finished.status == TransferStatus::Cancelled {
+            finished = discard_partial_transfer(&service, &queue, finished).await;
+        }
+        emit_status(&window, &finished);
+    });
+}
+
+async fn run_queued_transfer(
+    window: &Window,
+    queue: &TransferQueueState,
+    service: &S3Service,
+    job: &TransferJob,
+    cancel_token: &CancellationToken,
+) -> Result<(), S3Error> {
+    let reporter = ProgressReporter::new(window.clone(), job.id.clone(), job.key.clone());
+    let on_progress = |transferred, total| {
+        reporter.update(transferred, total);
+        queue.update_progress(&job.id, transferred, total);
+    };
+    let transfer = TransferContext {
+        on_progress: &on_progress,
+        cancel_token,
+        bandwidth: &window.state::<BandwidthState>(),
+    };
+    let path = PathBuf::from(&job.local_path);
+
+    match job.kind {
+        // Continues from the partial file an earlier run left behind. An existing file at
+        // the destination fails the job unless it was queued to overwrite.
+        TransferKind::Download => {
+            service.download_object(&job.bucket, &job.key, &path, job.overwrite, true, &transfer).await?;
+        }
+        TransferKind::Upload => {
+            let file_size = tokio::fs::metadata(&path)
+                .await
+                .map_err(|e| S3Error::IoError(format!("Failed to read {}: {}", path.display(), e)))?
+                .len();
+            if file_size < DEFAULT_MULTIPART_THRESHOLD {
+                service
+                    .upload_object(
+                        &job.bucket,
+                        &job.key,
+                        &path,
+                        None,
+                        DEFAULT_MULTIPART_THRESHOLD,
+                        None,
+                        ChecksumAlgorithm::default(),
+                        &Preconditions::default(),
+                        &transfer,
+                    )
+                    .await?;
+            } else {
+                // The upload id is saved before any part is sent so a restart can pick it up
+                let upload_id = match &job.upload_id {
+                    Some(upload_id) => upload_id.clone(),
+                    None => {
+                        let content_type = mime::content_type_for_file(&path).await;
+                        let upload_id = service
+                            .start_multipart_upload(&job.bucket, &job.key, &content_type, None, ChecksumAlgorithm::default())
+                            .await?;
+                        queue.update(&job.id, |job| job.upload_id = Some(upload_id.clone()));
+                        upload_id
+                    }
+                };
+                service
+                    .continue_multipart_upload(
+                        &job.bucket,
+                        &job.key,
+                        &upload_id,
+                        &path,
+                        DEFAULT_MULTIPART_THRESHOLD,
+                        ChecksumAlgorithm::default(),
+                        &Preconditions::default(),
+                        &transfer,
+                    )
+                    .await?;
+            }
+        }
+    }
+
+    reporter.complete();
+    Ok(())
+}
+
+// Aborts the open multipart upload or deletes the partial download of a cancelled job
+async fn discard_partial_transfer(service: &S3Service, queue: &TransferQueueState, job: TransferJob) -> TransferJob {
+    match job.kind {
+        TransferKind::Upload => {
+            let Some(upload_id) = &job.upload_id else {
+                return job;
+            };
+            if let Err(e) = service.abort_multipart_upload(&job.bucket, &job.key, upload_id).await {
+                warn!("Failed to abort multipart upload {} of transfer {}: {}", upload_id, job.id, e);
             }
         }
-        Err(err) => Err(format!("Failed to create S3 service: {}", err)),
+        TransferKind::Download => discard_partial_download(Path::new(&job.local_path)).await,
+    }
+    queue
+        .update(&job.id, |job| {
+            job.upload_id = None;
+            job.bytes_done = 0;
+        })
+        .unwrap_or(job)
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn delete_s3_object(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    confirmed: Option<bool>,
+    // Only deletes the object if it still has this ETag
+    if_match: Option<String>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    ensure_delete_confirmed(&settings_state, confirmed).await?;
+    let use_trash = trash_enabled(&settings_state).await;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    // Objects already in the trash are deleted for good
+    if use_trash && !key.starts_with(TRASH_PREFIX) {
+        return match service.trash_object(&bucket, &key, if_match.as_deref()).await {
+            Ok(_) => Ok(()),
+            Err(err) => Err(CommandError::with_context("Failed to move object to trash", err)),
+        };
+    }
+
+    match service.delete_object(&bucket, &key, if_match.as_deref()).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to delete object", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn trash_s3_object(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<String, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.trash_object(&bucket, &key, None).await {
+        Ok(trash_key) => Ok(trash_key),
+        Err(err) => Err(CommandError::with_context("Failed to move object to trash", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn restore_s3_trash(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    trash_key: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<String, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.restore_from_trash(&bucket, &trash_key).await {
+        Ok(original_key) => Ok(original_key),
+        Err(err) => Err(CommandError::with_context("Failed to restore object from trash", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn empty_s3_trash(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    confirmed: Option<bool>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<BatchResult, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    ensure_delete_confirmed(&settings_state, confirmed).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.empty_trash(&bucket).await {
+        Ok(result) => Ok(result),
+        Err(err) => Err(CommandError::with_context("Failed to empty trash", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_s3_objects(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    keys: Vec<String>,
+    confirmed: Option<bool>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Vec<DeleteResult>, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    ensure_delete_confirmed(&settings_state, confirmed).await?;
+    let use_trash = trash_enabled(&settings_state).await;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    let results = if use_trash {
+        service.trash_objects(&bucket, keys).await
+    } else {
+        service.delete_objects(&bucket, keys).await
+    };
+    match results {
+        Ok(results) => Ok(results),
+        Err(err) => Err(CommandError::with_context("Failed to delete objects", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn delete_s3_folder(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    prefix: String,
+    pattern: Option<String>,
+    confirmed: Option<bool>,
+    dry_run: Option<bool>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<BatchResult, CommandError> {
+    let dry_run = dry_run.unwrap_or(false);
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    // Previewing what a delete would remove needs no confirmation
+    if !dry_run {
+        ensure_delete_confirmed(&settings_state, confirmed).await?;
+    }
+    let pattern = match pattern.as_deref().filter(|p| !p.is_empty()) {
+        Some(p) => Some(KeyPattern::new(p).map_err(|err| err.to_string())?),
+        None => None,
+    };
+    let use_trash = trash_enabled(&settings_state).await;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    let result = if use_trash && !dry_run {
+        service.trash_folder(&bucket, &prefix, pattern.as_ref()).await
+    } else {
+        service.delete_folder(&bucket, &prefix, pattern.as_ref(), dry_run).await
+    };
+    match result {
+        Ok(result) => Ok(result),
+        Err(err) => Err(CommandError::with_context("Failed to delete folder", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn create_s3_bucket(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    region: Option<String>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    validate_bucket_name(&bucket)?;
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.create_bucket(&bucket, region.as_deref()).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to create bucket", err)),
+    }
+}
+
+// Deletes everything in the bucket, emitting the running totals after every page, and
+// then the bucket itself if asked to and nothing was left behind
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn empty_s3_bucket(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    include_versions: Option<bool>,
+    then_delete: Option<bool>,
+    confirmed: Option<bool>,
+    window: Window,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<EmptyBucketResult, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    ensure_delete_confirmed(&settings_state, confirmed).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    let on_page = |progress: &EmptyBucketResult| {
+        if let Err(e) = window.emit(EMPTY_BUCKET_PROGRESS_EVENT, progress.clone()) {
+            warn!("Failed to emit empty bucket progress: {}", e);
+        }
+    };
+    let mut result = service
+        .empty_bucket(&bucket, include_versions.unwrap_or(false), &on_page)
+        .await
+        .map_err(|err| CommandError::with_context("Failed to empty bucket", err))?;
+    info!("Emptied bucket {}: {} deleted, {} failed", bucket, result.deleted, result.failed.len());
+
+    if then_delete.unwrap_or(false) && result.failed.is_empty() {
+        service
+            .delete_bucket(&bucket)
+            .await
+            .map_err(|err| CommandError::with_context("Bucket was emptied but could not be deleted", err))?;
+        result.bucket_deleted = true;
+    }
+    Ok(result)
+}
+
+#[tauri::command]
+pub async fn delete_s3_bucket(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    confirmed: Option<bool>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    ensure_delete_confirmed(&settings_state, confirmed).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.delete_bucket(&bucket).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to delete bucket", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn create_s3_folder(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    folder_path: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.create_folder(&bucket, &folder_path).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to create folder", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_s3_download_url(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    expires_in_secs: u64,
+    response_content_disposition: Option<String>,
+    response_content_type: Option<String>,
+    // Checks the object exists first, at the cost of a HeadObject request
+    verify_exists: Option<bool>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<PresignedUrlResponse, CommandError> {
+    check_presign_policy(&settings_state, expires_in_secs).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service
+        .generate_presigned_download_url(
+            &bucket,
+            &key,
+            expires_in_secs,
+            response_content_disposition.as_deref(),
+            response_content_type.as_deref(),
+            verify_exists.unwrap_or(false),
+        )
+        .await
+    {
+        Ok(response) => Ok(response),
+        Err(err) => Err(CommandError::with_context("Failed to generate download URL", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_s3_upload_url(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    expires_in_secs: u64,
+    content_type: Option<String>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<PresignedUrlResponse, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    check_presign_policy(&settings_state, expires_in_secs).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.generate_presigned_upload_url(&bucket, &key, expires_in_secs, content_type.as_deref()).await {
+        Ok(response) => Ok(response),
+        Err(err) => Err(CommandError::with_context("Failed to generate upload URL", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn generate_s3_presigned_post(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    expires_in_secs: u64,
+    max_size_bytes: Option<u64>,
+    content_type_prefix: Option<String>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<PresignedPostResponse, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    check_presign_policy(&settings_state, expires_in_secs).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service
+        .generate_presigned_post(&bucket, &key, expires_in_secs, max_size_bytes, content_type_prefix.as_deref())
+        .await
+    {
+        Ok(response) => Ok(response),
+        Err(err) => Err(CommandError::with_context("Failed to generate presigned POST", err)),
+    }
+}
+
+// The object's plain, unsigned URL; only works if the object is publicly readable.
+// Built from the connection settings alone, without contacting the server.
+#[tauri::command]
+pub fn get_s3_public_url(connection_config: ConnectionConfig, bucket: String, key: String) -> Result<String, CommandError> {
+    let s3_config = to_s3_config(connection_config);
+    if s3_config.endpoint.trim().is_empty() {
+        return Err("Endpoint URL is required".into());
+    }
+    let region = match s3_config.region.trim() {
+        "" => "us-east-1",
+        region => region,
+    };
+
+    match object_url(s3_config.endpoint.trim(), s3_config.force_path_style, &bucket, &key, region) {
+        Ok(url) => Ok(url),
+        Err(err) => Err(CommandError::with_context("Failed to build public URL", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn copy_s3_object(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    source_bucket: String,
+    source_key: String,
+    dest_bucket: String,
+    dest_key: String,
+    options: Option<CopyOptions>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let options = options.unwrap_or_default();
+
+    match service.copy_object(&source_bucket, &source_key, &dest_bucket, &dest_key, &options).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to copy object", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn copy_s3_folder(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    source_bucket: String,
+    source_prefix: String,
+    dest_bucket: String,
+    dest_prefix: String,
+    max_concurrency: Option<usize>,
+    dry_run: Option<bool>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<BatchResult, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let concurrency = max_concurrency.unwrap_or(DEFAULT_COPY_CONCURRENCY);
+
+    match service
+        .copy_folder(&source_bucket, &source_prefix, &dest_bucket, &dest_prefix, concurrency, dry_run.unwrap_or(false))
+        .await
+    {
+        Ok(result) => Ok(result),
+        Err(err) => Err(CommandError::with_context("Failed to copy folder", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn move_s3_object(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    source_bucket: String,
+    source_key: String,
+    dest_bucket: String,
+    dest_key: String,
+    dry_run: Option<bool>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<BatchResult, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    // A dry run only checks that the source exists
+    if dry_run.unwrap_or(false) {
+        return match service.get_object_info(&source_bucket, &source_key).await {
+            Ok(_) => Ok(BatchResult::dry_run(vec![source_key])),
+            Err(err) => Err(CommandError::with_context("Failed to move object", err)),
+        };
+    }
+
+    match service.move_object(&source_bucket, &source_key, &dest_bucket, &dest_key).await {
+        Ok(_) => Ok(BatchResult { succeeded: vec![source_key], ..Default::default() }),
+        Err(err) => Err(CommandError::with_context("Failed to move object", err)),
+    }
+}
+
+// Moves the keys under `dest_prefix`, in `dest_bucket` when given. Keys keep their
+// paths below their shared folder with `preserve_structure`, or just their names.
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn move_s3_objects(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    keys: Vec<String>,
+    dest_prefix: String,
+    dest_bucket: Option<String>,
+    preserve_structure: Option<bool>,
+    max_concurrency: Option<usize>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Vec<KeyMove>, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    let dest_bucket = dest_bucket.unwrap_or_else(|| bucket.clone());
+    let concurrency = max_concurrency.unwrap_or(DEFAULT_COPY_CONCURRENCY);
+
+    match service
+        .move_objects(&bucket, &keys, &dest_bucket, &dest_prefix, preserve_structure.unwrap_or(false), concurrency)
+        .await
+    {
+        Ok(moves) => Ok(moves),
+        Err(err) => Err(CommandError::with_context("Failed to move objects", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn update_s3_object_metadata(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    content_type: Option<String>,
+    cache_control: Option<String>,
+    metadata: HashMap<String, String>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<String, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service
+        .update_object_metadata(&bucket, &key, content_type.as_deref(), cache_control.as_deref(), &metadata)
+        .await
+    {
+        Ok(etag) => Ok(etag),
+        Err(err) => Err(CommandError::with_context("Failed to update object metadata", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_object_storage_class(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    storage_class: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<String, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.set_object_storage_class(&bucket, &key, &storage_class).await {
+        Ok(storage_class) => Ok(storage_class),
+        Err(err) => Err(CommandError::with_context("Failed to change storage class", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_object_acl(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<ObjectAcl, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_object_acl(&bucket, &key).await {
+        Ok(acl) => Ok(acl),
+        Err(err) => Err(CommandError::with_context("Failed to get object ACL", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_object_acl(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    canned_acl: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.set_object_acl(&bucket, &key, &canned_acl).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set object ACL", err)),
+    }
+}
+
+// Applies the public-read ACL and returns the object's public URL
+#[tauri::command]
+pub async fn make_s3_object_public(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<String, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.make_object_public(&bucket, &key).await {
+        Ok(url) => Ok(url),
+        Err(err) => Err(CommandError::with_context("Failed to make object public", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_bucket_versioning(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<BucketVersioningStatus, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_bucket_versioning(&bucket).await {
+        Ok(status) => Ok(status),
+        Err(err) => Err(CommandError::with_context("Failed to get bucket versioning", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_bucket_versioning(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    enabled: bool,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.set_bucket_versioning(&bucket, enabled).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set bucket versioning", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn list_s3_object_versions(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    prefix: Option<String>,
+    key_marker: Option<String>,
+    version_id_marker: Option<String>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<ListObjectVersionsResponse, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.list_object_versions(
+        &bucket,
+        prefix.as_deref(),
+        key_marker.as_deref(),
+        version_id_marker.as_deref(),
+    ).await {
+        Ok(response) => Ok(response),
+        Err(err) => Err(CommandError::with_context("Failed to list object versions", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn restore_s3_object_version(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    version_id: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.restore_object_version(&bucket, &key, &version_id).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to restore object version", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn remove_s3_delete_marker(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    version_id: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.remove_delete_marker(&bucket, &key, &version_id).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to remove delete marker", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_object_tags(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Vec<ObjectTag>, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_object_tags(&bucket, &key).await {
+        Ok(tags) => Ok(tags),
+        Err(err) => Err(CommandError::with_context("Failed to get object tags", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_object_tags(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    tags: Vec<ObjectTag>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    match service.set_object_tags(&bucket, &key, &tags).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set object tags", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn clear_s3_object_tags(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.clear_object_tags(&bucket, &key).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to clear object tags", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_bucket_tags(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Vec<ObjectTag>, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_bucket_tags(&bucket).await {
+        Ok(tags) => Ok(tags),
+        Err(err) => Err(CommandError::with_context("Failed to get bucket tags", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_bucket_tags(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    tags: Vec<ObjectTag>,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    match service.set_bucket_tags(&bucket, &tags).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set bucket tags", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_s3_bucket_tags(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.delete_bucket_tags(&bucket).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to delete bucket tags", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_object_retention(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<ObjectRetention, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_object_retention(&bucket, &key).await {
+        Ok(retention) => Ok(retention),
+        Err(err) => Err(CommandError::with_context("Failed to get object retention", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn set_s3_object_retention(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    mode: RetentionMode,
+    retain_until: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.set_object_retention(&bucket, &key, mode, &retain_until).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set object retention", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_object_legal_hold(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    on: bool,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.set_object_legal_hold(&bucket, &key, on).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set legal hold", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn list_s3_multipart_uploads(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    prefix: Option<String>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Vec<MultipartUploadInfo>, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.list_multipart_uploads(&bucket, prefix.as_deref()).await {
+        Ok(uploads) => Ok(uploads),
+        Err(err) => Err(CommandError::with_context("Failed to list multipart uploads", err)),
+    }
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn abort_s3_multipart_upload(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    key: String,
+    upload_id: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.abort_multipart_upload(&bucket, &key, &upload_id).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to abort multipart upload", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn cleanup_s3_incomplete_uploads(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    older_than_hours: u64,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<BatchResult, CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.cleanup_incomplete_uploads(&bucket, older_than_hours).await {
+        Ok(result) => Ok(result),
+        Err(err) => Err(CommandError::with_context("Failed to clean up incomplete uploads", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_bucket_encryption(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Option<EncryptionConfig>, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_bucket_encryption(&bucket).await {
+        Ok(config) => Ok(config),
+        Err(err) => Err(CommandError::with_context("Failed to get bucket encryption", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_bucket_encryption(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    config: EncryptionConfig,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.set_bucket_encryption(&bucket, &config).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set bucket encryption", err)),
+    }
+}
+
+// Resolves to None when website hosting isn't enabled for the bucket
+#[tauri::command]
+pub async fn get_s3_bucket_website(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<Option<WebsiteConfig>, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_bucket_website(&bucket).await {
+        Ok(config) => Ok(config),
+        Err(err) => Err(CommandError::with_context("Failed to get bucket website", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_bucket_website(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    config: WebsiteConfig,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.set_bucket_website(&bucket, &config).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set bucket website", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_s3_bucket_website(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.delete_bucket_website(&bucket).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to delete bucket website", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_bucket_logging(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<LoggingConfig, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_bucket_logging(&bucket).await {
+        Ok(config) => Ok(config),
+        Err(err) => Err(CommandError::with_context("Failed to get bucket logging", err)),
+    }
+}
+
+// The target bucket and prefix are ignored when `enabled` is false
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn set_s3_bucket_logging(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    target_bucket: Option<String>,
+    target_prefix: Option<String>,
+    enabled: bool,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service
+        .set_bucket_logging(&bucket, target_bucket.as_deref(), target_prefix.as_deref(), enabled)
+        .await
+    {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set bucket logging", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_bucket_notification(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<NotificationConfig, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_bucket_notification(&bucket).await {
+        Ok(config) => Ok(config),
+        Err(err) => Err(CommandError::with_context("Failed to get bucket notifications", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn set_s3_bucket_notification(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    config: NotificationConfig,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.set_bucket_notification(&bucket, &config).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to set bucket notifications", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn delete_s3_bucket_notification(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    settings_state: State<'_, SettingsState>,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<(), CommandError> {
+    ensure_writable(&settings_state, &connection_name, connection_config.as_ref()).await?;
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.delete_bucket_notification(&bucket).await {
+        Ok(_) => Ok(()),
+        Err(err) => Err(CommandError::with_context("Failed to delete bucket notifications", err)),
+    }
+}
+
+// Never fails on a probe: whatever couldn't be confirmed is reported as unsupported
+#[tauri::command]
+pub async fn get_s3_bucket_capabilities(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<BucketCapabilities, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+    Ok(service.get_bucket_capabilities(&bucket).await)
+}
+
+// Uses HeadBucket, which is much cheaper than listing, to validate a bucket name
+#[tauri::command]
+pub async fn check_s3_bucket_access(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<BucketAccess, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.check_bucket_access(&bucket).await {
+        Ok(access) => Ok(access),
+        Err(err) => Err(CommandError::with_context("Failed to check bucket access", err)),
+    }
+}
+
+#[tauri::command]
+pub async fn get_s3_bucket_location(
+    connection_name: String,
+    connection_config: Option<ConnectionConfig>,
+    bucket: String,
+    s3_state: State<'_, S3ConnectionState>,
+) -> Result<String, CommandError> {
+    let service = get_service(&s3_state, &connection_name, connection_config).await?;
+
+    match service.get_bucket_location(&bucket).await {
+        Ok(location) => Ok(location),
+        Err(err) => Err(CommandError::with_context("Failed to get bucket location", err)),
     }
 }
\ No newline at end of file
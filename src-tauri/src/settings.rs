@@ -1,7 +1,22 @@
+use crate::auto_refresh;
+use crate::command_error::{CommandError, ErrorCode};
+use crate::bandwidth::BandwidthState;
+use crate::format::SizeUnits;
+use crate::keychain;
+use crate::listing_cache;
+use crate::logging;
+use crate::providers;
+use crate::settings_crypto;
+use crate::transfer;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::path::PathBuf;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tauri::Manager;
 use tokio::fs;
+use tokio::io::AsyncWriteExt;
+use tracing::{error, info, warn};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GeneralSettings {
@@ -10,6 +25,38 @@ pub struct GeneralSettings {
     pub default_download_location: String,
     pub confirm_before_delete: bool,
     pub show_file_preview: bool,
+    // One of off, error, warn, info, debug, trace
+    #[serde(default = "default_log_level")]
+    pub log_level: String,
+    // Deletes move objects to a trash prefix in the bucket instead of removing them
+    #[serde(default)]
+    pub use_trash: bool,
+    // Uploads and downloads beyond this many wait for a free slot
+    #[serde(default = "default_max_concurrent_transfers")]
+    pub max_concurrent_transfers: u32,
+    // Shared cap on upload and download throughput; None is unlimited
+    #[serde(default)]
+    pub max_bytes_per_sec: Option<u64>,
+    // Base used for human-readable object sizes
+    #[serde(default)]
+    pub size_units: SizeUnits,
+    // Connect to the default connection as soon as settings are loaded
+    #[serde(default)]
+    pub auto_connect_default: bool,
+}
+
+fn default_log_level() -> String {
+    logging::DEFAULT_LOG_LEVEL.to_string()
+}
+
+fn default_max_concurrent_transfers() -> u32 {
+    transfer::DEFAULT_MAX_CONCURRENT_TRANSFERS
+}
+
+fn validate_general_settings(general: &GeneralSettings) -> Result<(), String> {
+    logging::parse_level(&general.log_level)?;
+    transfer::validate_max_concurrent_transfers(general.max_concurrent_transfers)?;
+    Ok(())
 }
 
 impl Default for GeneralSettings {
@@ -20,6 +67,12 @@ impl Default for GeneralSettings {
             default_download_location: String::new(),
             confirm_before_delete: true,
             show_file_preview: true,
+            log_level: default_log_level(),
+            use_trash: false,
+            max_concurrent_transfers: default_max_concurrent_transfers(),
+            max_bytes_per_sec: None,
+            size_units: SizeUnits::default(),
+            auto_connect_default: false,
         }
     }
 }
@@ -33,6 +86,82 @@ pub struct ConnectionConfig {
     pub secret_key: String,
     pub region: String,
     pub is_default: bool,
+    // None falls back to path-style for custom endpoints and virtual-hosted for AWS
+    #[serde(default)]
+    pub force_path_style: Option<bool>,
+    // Disables TLS certificate verification, for self-signed MinIO/Ceph deployments
+    #[serde(default)]
+    pub allow_invalid_certs: bool,
+    // Temporary STS credentials (assume-role, ECS/EKS roles)
+    #[serde(default)]
+    pub session_token: Option<String>,
+    #[serde(default)]
+    pub expiration: Option<DateTime<Utc>>,
+    // Per-request timeout and retry count; None keeps the SDK defaults, 0 retries disables retrying
+    #[serde(default)]
+    pub timeout_secs: Option<u64>,
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    #[serde(default)]
+    pub proxy_url: Option<String>,
+    // Connections kept open per host; None (the default) leaves them unlimited. More lets
+    // batch operations run in parallel, fewer eases load on servers that throttle
+    // (S3 SlowDown, MinIO's request limit) at the cost of requests queueing for a connection.
+    #[serde(default)]
+    pub max_connections: Option<u32>,
+    // Other endpoints serving the same data, tried in order when `endpoint` is unreachable
+    #[serde(default)]
+    pub fallback_endpoints: Vec<String>,
+    // Public buckets only: requests are sent unsigned and the keys may be left empty
+    #[serde(default)]
+    pub anonymous: bool,
+    // Sends x-amz-request-payer so requester-pays buckets can be read; the requests are billed to this account
+    #[serde(default)]
+    pub request_payer: bool,
+    // Only honoured for AWS endpoints
+    #[serde(default)]
+    pub use_accelerate: bool,
+    #[serde(default)]
+    pub use_dualstack: bool,
+    // Credentials come from the environment or ~/.aws instead of the keys above
+    #[serde(default)]
+    pub use_default_credential_chain: bool,
+    #[serde(default)]
+    pub profile_name: Option<String>,
+    // Enforced by the backend: every command that writes or deletes is refused
+    #[serde(default)]
+    pub read_only: bool,
+    // Maintained by the backend; whatever the frontend sends is ignored on update
+    #[serde(default, flatten)]
+    pub stats: ConnectionStats,
+}
+
+impl ConnectionConfig {
+    // Whether both reach the same endpoint with the same credentials, whatever else differs
+    pub fn same_target(&self, other: &ConnectionConfig) -> bool {
+        self.endpoint == other.endpoint
+            && self.region == other.region
+            && self.access_key == other.access_key
+            && self.secret_key == other.secret_key
+            && self.session_token == other.session_token
+            && self.anonymous == other.anonymous
+            && self.use_default_credential_chain == other.use_default_credential_chain
+            && self.profile_name == other.profile_name
+    }
+}
+
+// Outcome of connecting to and testing a saved connection
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionStats {
+    #[serde(default)]
+    pub last_connected_at: Option<DateTime<Utc>>,
+    // Cleared by the next successful attempt
+    #[serde(default)]
+    pub last_error: Option<String>,
+    #[serde(default)]
+    pub connect_count: u32,
+    #[serde(default)]
+    pub failure_count: u32,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -59,6 +188,16 @@ pub struct LayoutSettings {
     pub default_view: String,
     pub sort_by: String,
     pub sort_direction: String,
+    // Keys per listing page when the caller doesn't ask for a number; 1 to 1000
+    #[serde(default = "default_page_size")]
+    pub default_page_size: i32,
+}
+
+// S3 never returns more than this many keys per page
+pub const MAX_PAGE_SIZE: i32 = 1000;
+
+fn default_page_size() -> i32 {
+    MAX_PAGE_SIZE
 }
 
 impl Default for LayoutSettings {
@@ -67,6 +206,7 @@ impl Default for LayoutSettings {
             default_view: "list".to_string(),
             sort_by: "name".to_string(),
             sort_direction: "asc".to_string(),
+            default_page_size: default_page_size(),
         }
     }
 }
@@ -75,6 +215,16 @@ impl Default for LayoutSettings {
 pub struct PermissionsSettings {
     pub allow_anonymous_usage_stats: bool,
     pub enable_caching: bool,
+    // How long a cached object listing is served before it's fetched again
+    #[serde(default = "default_cache_ttl_secs")]
+    pub cache_ttl_secs: u64,
+    // Policy cap on presigned URL lifetimes, on top of SigV4's 7 days; None means no cap
+    #[serde(default)]
+    pub max_presign_expiry_secs: Option<u64>,
+}
+
+fn default_cache_ttl_secs() -> u64 {
+    listing_cache::DEFAULT_LISTING_CACHE_TTL_SECS
 }
 
 impl Default for PermissionsSettings {
@@ -82,10 +232,89 @@ impl Default for PermissionsSettings {
         Self {
             allow_anonymous_usage_stats: false,
             enable_caching: true,
+            cache_ttl_secs: default_cache_ttl_secs(),
+            max_presign_expiry_secs: None,
         }
     }
 }
 
+const MAX_RECENT_PATHS: usize = 20;
+// Sent with ErrorCode::PasswordRequired, which the frontend answers by asking for the password
+pub const PASSWORD_REQUIRED_ERROR: &str = "Settings file is password protected";
+pub const CURRENT_SETTINGS_VERSION: &str = "1.1.0";
+
+type Migration = fn(&mut Value);
+
+// Each entry upgrades the raw settings JSON from one version to the next
+const MIGRATIONS: &[(&str, &str, Migration)] = &[("1.0.0", "1.1.0", migrate_1_0_to_1_1)];
+
+// 1.1.0 added per-connection transport and credential options and the connection history
+fn migrate_1_0_to_1_1(settings: &mut Value) {
+    if let Some(connections) = settings.get_mut("connections").and_then(Value::as_array_mut) {
+        for connection in connections.iter_mut().filter_map(Value::as_object_mut) {
+            for field in ["force_path_style", "session_token", "expiration", "timeout_secs", "max_retries", "proxy_url"] {
+                connection.entry(field).or_insert(Value::Null);
+            }
+            connection.entry("allow_invalid_certs").or_insert(Value::Bool(false));
+        }
+    }
+    if let Some(settings) = settings.as_object_mut() {
+        settings.entry("connection_history").or_insert_with(|| json!({}));
+    }
+}
+
+// Runs the migration chain up to the current version. Returns true if anything changed.
+fn migrate_settings(settings: &mut Value) -> bool {
+    let mut migrated = false;
+    loop {
+        let version = settings
+            .get("version")
+            .and_then(Value::as_str)
+            .unwrap_or("1.0.0")
+            .to_string();
+        if version == CURRENT_SETTINGS_VERSION {
+            return migrated;
+        }
+
+        match MIGRATIONS.iter().find(|(from, _, _)| *from == version) {
+            Some((from, to, migrate)) => {
+                info!("Migrating settings from version {} to {}", from, to);
+                migrate(settings);
+                settings["version"] = json!(to);
+                migrated = true;
+            }
+            None => {
+                // Most likely written by a newer release; serde defaults cover what they can
+                warn!("Unknown settings version {}, loading as is", version);
+                return migrated;
+            }
+        }
+    }
+}
+
+// Parses a settings file of any known version. The flag is true if it had to be migrated.
+fn parse_settings(content: &str) -> Result<(AppSettings, bool), serde_json::Error> {
+    let mut value: Value = serde_json::from_str(content)?;
+    let migrated = migrate_settings(&mut value);
+    Ok((serde_json::from_value(value)?, migrated))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecentPath {
+    pub bucket: String,
+    pub prefix: String,
+    pub accessed_at: DateTime<Utc>,
+}
+
+// Favorites are "bucket/prefix" paths; recent paths are newest first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConnectionHistory {
+    #[serde(default)]
+    pub favorites: Vec<String>,
+    #[serde(default)]
+    pub recent_paths: Vec<RecentPath>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AppSettings {
     pub version: String,
@@ -94,24 +323,37 @@ pub struct AppSettings {
     pub appearance: AppearanceSettings,
     pub layout: LayoutSettings,
     pub permissions: PermissionsSettings,
+    // Keyed by connection name
+    #[serde(default)]
+    pub connection_history: HashMap<String, ConnectionHistory>,
 }
 
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
-            version: "1.0.0".to_string(),
+            version: CURRENT_SETTINGS_VERSION.to_string(),
             general: GeneralSettings::default(),
             connections: vec![],
             appearance: AppearanceSettings::default(),
             layout: LayoutSettings::default(),
             permissions: PermissionsSettings::default(),
+            connection_history: HashMap::new(),
         }
     }
 }
 
+fn session_token_account(connection_name: &str) -> String {
+    format!("{}/session-token", connection_name)
+}
+
 pub struct SettingsManager {
     settings_path: PathBuf,
     current_settings: AppSettings,
+    // Secrets known to be in the keychain, by account, so saves only write the ones that changed
+    keychain_secrets: HashMap<String, String>,
+    bandwidth: BandwidthState,
+    // Connection stats changed since the last save; they go out with the next one
+    stats_dirty: bool,
 }
 
 impl SettingsManager {
@@ -131,70 +373,257 @@ impl SettingsManager {
         Ok(Self {
             settings_path,
             current_settings: AppSettings::default(),
+            keychain_secrets: HashMap::new(),
+            stats_dirty: false,
+            bandwidth: app_handle.state::<BandwidthState>().inner().clone(),
         })
     }
 
     pub async fn load_settings(&mut self) -> Result<AppSettings, Box<dyn std::error::Error>> {
-        if self.settings_path.exists() {
-            let content = fs::read_to_string(&self.settings_path).await?;
-            match serde_json::from_str::<AppSettings>(&content) {
-                Ok(settings) => {
-                    self.current_settings = settings.clone();
-                    Ok(settings)
+        let backup_path = self.sibling_path("bak");
+        if !self.settings_path.exists() && !backup_path.exists() {
+            // Create default settings file
+            self.current_settings = AppSettings::default();
+            self.save_settings().await?;
+            return Ok(self.current_settings.clone());
+        }
+
+        // Stringified so no boxed error is held across the awaits below, which keeps this future Send
+        let read = Self::read_settings_file(&self.settings_path).await.map_err(|e| e.to_string());
+        let (mut settings, needs_rewrite) = match read {
+            Ok((settings, migrated)) => (settings, migrated),
+            Err(e) => {
+                error!("Failed to read settings file: {}", e);
+                // Keep the broken file around for inspection instead of overwriting it
+                if self.settings_path.exists() {
+                    fs::rename(&self.settings_path, self.sibling_path("corrupt")).await?;
                 }
-                Err(e) => {
-                    eprintln!("Failed to parse settings file: {}", e);
-                    // Return default settings if parsing fails
-                    self.current_settings = AppSettings::default();
-                    self.save_settings().await?;
-                    Ok(self.current_settings.clone())
+                match Self::read_settings_file(&backup_path).await {
+                    Ok((settings, _)) => {
+                        warn!("Recovered settings from {}", backup_path.display());
+                        (settings, true)
+                    }
+                    Err(e) => {
+                        error!("Failed to read settings backup, using defaults: {}", e);
+                        (AppSettings::default(), true)
+                    }
                 }
             }
-        } else {
-            // Create default settings file
-            self.current_settings = AppSettings::default();
+        };
+
+        let has_plaintext_secrets = self.resolve_secrets(&mut settings);
+        self.current_settings = settings.clone();
+        if needs_rewrite || has_plaintext_secrets {
+            // Rewrites a recovered or upgraded file, and moves secrets saved by older versions into the keychain
             self.save_settings().await?;
-            Ok(self.current_settings.clone())
+        } else {
+            self.apply_general_settings();
         }
+        Ok(settings)
+    }
+
+    async fn read_settings_file(path: &Path) -> Result<(AppSettings, bool), Box<dyn std::error::Error>> {
+        let content = fs::read_to_string(path).await?;
+        Ok(parse_settings(&content)?)
     }
 
-    pub async fn save_settings(&self) -> Result<(), Box<dyn std::error::Error>> {
-        let content = serde_json::to_string_pretty(&self.current_settings)?;
-        fs::write(&self.settings_path, content).await?;
+    // settings.json.<extension> next to the settings file
+    fn sibling_path(&self, extension: &str) -> PathBuf {
+        self.settings_path.with_extension(format!("json.{}", extension))
+    }
+
+    // Writes to a temporary file and renames it into place so a crash never leaves a
+    // half-written settings.json. The previous file is kept as settings.json.bak.
+    pub async fn save_settings(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        let mut persisted = self.current_settings.clone();
+        for conn in &mut persisted.connections {
+            self.persist_secret(&conn.name, &mut conn.secret_key);
+            if let Some(token) = conn.session_token.as_mut() {
+                self.persist_secret(&session_token_account(&conn.name), token);
+            }
+        }
+
+        let content = serde_json::to_string_pretty(&persisted)?;
+        let temp_path = self.sibling_path("tmp");
+        let mut file = fs::File::create(&temp_path).await?;
+        file.write_all(content.as_bytes()).await?;
+        file.sync_all().await?;
+        drop(file);
+
+        if self.settings_path.exists() {
+            fs::rename(&self.settings_path, self.sibling_path("bak")).await?;
+        }
+        fs::rename(&temp_path, &self.settings_path).await?;
+        self.stats_dirty = false;
+        self.apply_general_settings();
         Ok(())
     }
 
+    // Saves connection stats recorded since the last save, e.g. on the way out
+    pub async fn flush_stats(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.stats_dirty {
+            return Ok(());
+        }
+        self.save_settings().await
+    }
+
+    // Pushes the settings that take effect at runtime to the logger and the refresh timer
+    fn apply_general_settings(&self) {
+        let general = &self.current_settings.general;
+        if let Err(e) = logging::set_level(&general.log_level) {
+            warn!("{}", e);
+        }
+        auto_refresh::configure(general.auto_refresh, general.refresh_interval);
+        self.bandwidth.set_limit(general.max_bytes_per_sec);
+    }
+
+    // Moves a secret into the keychain and replaces it with a placeholder. The keychain
+    // is only written when the secret changed. If no keychain is available the secret
+    // is left as is and ends up in the settings file.
+    fn persist_secret(&mut self, account: &str, secret: &mut String) {
+        if secret.is_empty() || keychain::is_placeholder(secret) {
+            return;
+        }
+        if self.keychain_secrets.get(account) == Some(secret) {
+            *secret = keychain::placeholder_for(account);
+            return;
+        }
+        match keychain::store_secret(account, secret) {
+            Ok(()) => {
+                self.keychain_secrets.insert(account.to_string(), secret.clone());
+                *secret = keychain::placeholder_for(account);
+            }
+            Err(e) => warn!(
+                "Keychain unavailable, storing secret for '{}' in settings file: {}",
+                account, e
+            ),
+        }
+    }
+
+    // Replaces keychain placeholders with the real secrets. Returns true if any
+    // connection still has a plaintext secret that should be moved to the keychain.
+    fn resolve_secrets(&mut self, settings: &mut AppSettings) -> bool {
+        let mut has_plaintext_secrets = false;
+        for conn in &mut settings.connections {
+            has_plaintext_secrets |= self.resolve_secret(&mut conn.secret_key);
+            if let Some(token) = conn.session_token.as_mut() {
+                has_plaintext_secrets |= self.resolve_secret(token);
+            }
+        }
+        has_plaintext_secrets
+    }
+
+    // Returns true if the value is a plaintext secret rather than a keychain placeholder.
+    // A secret that can't be read keeps its placeholder, which S3Service refuses to sign with.
+    fn resolve_secret(&mut self, secret: &mut String) -> bool {
+        if !keychain::is_placeholder(secret) {
+            return !secret.is_empty();
+        }
+        match keychain::resolve_placeholder(secret) {
+            Ok(resolved) => {
+                let account = keychain::account_for(secret).to_string();
+                self.keychain_secrets.insert(account, resolved.clone());
+                *secret = resolved;
+            }
+            Err(e) => error!("Failed to read '{}' from keychain: {}", secret, e),
+        }
+        false
+    }
+
+    fn forget_secret(&mut self, connection_name: &str) {
+        for account in [connection_name.to_string(), session_token_account(connection_name)] {
+            self.keychain_secrets.remove(&account);
+            if let Err(e) = keychain::delete_secret(&account) {
+                warn!("Failed to remove secret for '{}' from keychain: {}", account, e);
+            }
+        }
+    }
+
+    // For when the whole connection list is replaced: the secrets of connections that
+    // aren't in the new list would otherwise stay in the keychain for good
+    fn forget_dropped_secrets(&mut self, connections: &[ConnectionConfig]) {
+        let dropped: Vec<String> = self
+            .current_settings
+            .connections
+            .iter()
+            .filter(|conn| !connections.iter().any(|kept| kept.name == conn.name))
+            .map(|conn| conn.name.clone())
+            .collect();
+        for name in dropped {
+            self.forget_secret(&name);
+        }
+    }
+
     pub async fn update_settings(&mut self, settings: AppSettings) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        validate_general_settings(&settings.general)?;
+        for (i, connection) in settings.connections.iter().enumerate() {
+            Self::validate_connection(&settings.connections, connection, Some(i))?;
+        }
         self.current_settings = settings;
         self.save_settings().await?;
         Ok(self.current_settings.clone())
     }
 
+    pub fn connection(&self, name: &str) -> Option<&ConnectionConfig> {
+        self.current_settings.connections.iter().find(|conn| conn.name == name)
+    }
+
+    pub fn default_connection(&self) -> Option<&ConnectionConfig> {
+        self.current_settings.connections.iter().find(|conn| conn.is_default)
+    }
+
+    pub fn general_settings(&self) -> &GeneralSettings {
+        &self.current_settings.general
+    }
+
+    pub fn layout_settings(&self) -> &LayoutSettings {
+        &self.current_settings.layout
+    }
+
+    pub fn permissions_settings(&self) -> &PermissionsSettings {
+        &self.current_settings.permissions
+    }
+
     pub fn get_current_settings(&self) -> AppSettings {
         self.current_settings.clone()
     }
 
-    pub async fn export_settings(&self, export_path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
-        let content = serde_json::to_string_pretty(&self.current_settings)?;
+    // Exports contain the real secrets, so a password encrypts the whole file
+    pub async fn export_settings(&self, export_path: PathBuf, password: Option<&str>) -> Result<(), Box<dyn std::error::Error>> {
+        let mut content = serde_json::to_string_pretty(&self.current_settings)?;
+        if let Some(password) = password {
+            content = settings_crypto::encrypt(content, password.to_string()).await?;
+        }
         fs::write(export_path, content).await?;
         Ok(())
     }
 
-    pub async fn import_settings(&mut self, import_path: PathBuf) -> Result<AppSettings, Box<dyn std::error::Error>> {
-        let content = fs::read_to_string(import_path).await?;
-        let imported_settings: AppSettings = serde_json::from_str(&content)?;
+    pub async fn import_settings(&mut self, import_path: PathBuf, password: Option<&str>) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        let mut content = fs::read_to_string(import_path).await?;
+        if settings_crypto::is_encrypted(&content) {
+            match password {
+                Some(password) => content = settings_crypto::decrypt(content, password.to_string()).await?,
+                None => return Err(Box::new(CommandError::new(ErrorCode::PasswordRequired, PASSWORD_REQUIRED_ERROR))),
+            }
+        }
+        let (mut imported_settings, _) = parse_settings(&content)?;
+        self.forget_dropped_secrets(&imported_settings.connections);
+        self.resolve_secrets(&mut imported_settings);
         self.current_settings = imported_settings;
         self.save_settings().await?;
         Ok(self.current_settings.clone())
     }
 
     pub async fn reset_to_defaults(&mut self) -> Result<AppSettings, Box<dyn std::error::Error>> {
-        self.current_settings = AppSettings::default();
+        let defaults = AppSettings::default();
+        self.forget_dropped_secrets(&defaults.connections);
+        self.current_settings = defaults;
         self.save_settings().await?;
         Ok(self.current_settings.clone())
     }
 
     pub async fn update_general_settings(&mut self, general: GeneralSettings) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        validate_general_settings(&general)?;
         self.current_settings.general = general;
         self.save_settings().await?;
         Ok(self.current_settings.clone())
@@ -218,7 +647,59 @@ impl SettingsManager {
         Ok(self.current_settings.clone())
     }
 
+    // Names key the keychain entries and the history, so they must be unique. Reusing
+    // another connection's endpoint and access key is allowed but probably a mistake.
+    // `connections` is the list the connection is or will be at `index` in.
+    fn validate_connection(
+        connections: &[ConnectionConfig],
+        connection: &ConnectionConfig,
+        index: Option<usize>,
+    ) -> Result<(), String> {
+        if connection.name.trim().is_empty() {
+            return Err("Connection name cannot be empty".to_string());
+        }
+        if connection.anonymous && connection.use_default_credential_chain {
+            return Err("Anonymous access and the default credential chain can't be combined".to_string());
+        }
+        let uses_keys = !connection.anonymous && !connection.use_default_credential_chain;
+        if uses_keys && (connection.access_key.trim().is_empty() || connection.secret_key.trim().is_empty()) {
+            return Err("An access key and secret key are required unless the default credential chain is used".to_string());
+        }
+        if connection.max_connections == Some(0) {
+            return Err("The connection limit must be at least 1".to_string());
+        }
+        // Connecting fills an empty endpoint in from the service type's preset
+        let has_endpoint = !connection.endpoint.trim().is_empty()
+            || providers::resolve_endpoint(&connection.service_type, &connection.region, None).is_ok();
+        if !has_endpoint {
+            return Err(format!("Connection '{}' needs an endpoint URL", connection.name));
+        }
+        let others = connections.iter().enumerate().filter(|(i, _)| Some(*i) != index);
+        for (_, other) in others {
+            if other.name == connection.name {
+                return Err(format!("A connection named '{}' already exists", connection.name));
+            }
+            let same_keys = uses_keys && other.access_key == connection.access_key;
+            if other.endpoint == connection.endpoint && same_keys {
+                warn!(
+                    "Connection '{}' uses the same endpoint and access key as '{}'",
+                    connection.name, other.name
+                );
+            }
+        }
+        let is_custom_endpoint = !connection.endpoint.trim().is_empty() && !connection.endpoint.contains("amazonaws.com");
+        if is_custom_endpoint && (connection.use_accelerate || connection.use_dualstack) {
+            warn!(
+                "Connection '{}' enables Transfer Acceleration or dualstack, which only apply to AWS endpoints",
+                connection.name
+            );
+        }
+        Ok(())
+    }
+
     pub async fn add_connection(&mut self, connection: ConnectionConfig) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        Self::validate_connection(&self.current_settings.connections, &connection, None)?;
+
         // If this is set as default, unset other defaults
         if connection.is_default {
             for conn in &mut self.current_settings.connections {
@@ -235,6 +716,7 @@ impl SettingsManager {
         if index >= self.current_settings.connections.len() {
             return Err("Connection index out of bounds".into());
         }
+        Self::validate_connection(&self.current_settings.connections, &connection, Some(index))?;
 
         // If this is set as default, unset other defaults
         if connection.is_default {
@@ -245,18 +727,327 @@ impl SettingsManager {
             }
         }
 
-        self.current_settings.connections[index] = connection;
+        let previous_name = self.current_settings.connections[index].name.clone();
+        if previous_name != connection.name {
+            self.forget_secret(&previous_name);
+            if let Some(history) = self.current_settings.connection_history.remove(&previous_name) {
+                self.current_settings.connection_history.insert(connection.name.clone(), history);
+            }
+        }
+
+        let stats = std::mem::take(&mut self.current_settings.connections[index].stats);
+        self.current_settings.connections[index] = ConnectionConfig { stats, ..connection };
         self.save_settings().await?;
         Ok(self.current_settings.clone())
     }
 
+    // Adds a copy of a connection right after it, named "<name> (copy)" or
+    // "<name> (copy N)" if that's taken. The copy is never the default.
+    pub async fn duplicate_connection(&mut self, index: usize) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        let Some(original) = self.current_settings.connections.get(index) else {
+            return Err("Connection index out of bounds".into());
+        };
+
+        let name_taken = |name: &str| self.current_settings.connections.iter().any(|conn| conn.name == name);
+        let mut name = format!("{} (copy)", original.name);
+        let mut n = 2;
+        while name_taken(&name) {
+            name = format!("{} (copy {})", original.name, n);
+            n += 1;
+        }
+
+        let copy = ConnectionConfig {
+            name,
+            is_default: false,
+            stats: ConnectionStats::default(),
+            ..original.clone()
+        };
+        self.current_settings.connections.insert(index + 1, copy);
+        self.save_settings().await?;
+        Ok(self.current_settings.clone())
+    }
+
+    // Moves the connection at `from_index` so it ends up at `to_index`, shifting the ones in between
+    pub async fn reorder_connections(&mut self, from_index: usize, to_index: usize) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        let connections = &mut self.current_settings.connections;
+        if from_index >= connections.len() || to_index >= connections.len() {
+            return Err("Connection index out of bounds".into());
+        }
+
+        let connection = connections.remove(from_index);
+        connections.insert(to_index, connection);
+        self.save_settings().await?;
+        Ok(self.current_settings.clone())
+    }
+
+    pub async fn move_connection_to_top(&mut self, index: usize) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        self.reorder_connections(index, 0).await
+    }
+
+    // Records a connect or test of a saved connection; unsaved connections are ignored.
+    // Only kept in memory until the next save, so testing a connection over and over
+    // doesn't rewrite the settings file and push the last good copy out of the backup.
+    pub fn record_connection_attempt(&mut self, name: &str, result: Result<(), String>) {
+        let Some(connection) = self.current_settings.connections.iter_mut().find(|conn| conn.name == name) else {
+            return;
+        };
+
+        let stats = &mut connection.stats;
+        match result {
+            Ok(()) => {
+                stats.last_connected_at = Some(Utc::now());
+                stats.last_error = None;
+                stats.connect_count = stats.connect_count.saturating_add(1);
+            }
+            Err(error) => {
+                stats.last_error = Some(error);
+                stats.failure_count = stats.failure_count.saturating_add(1);
+            }
+        }
+        self.stats_dirty = true;
+    }
+
+    pub fn connection_stats(&self, index: usize) -> Option<&ConnectionStats> {
+        self.current_settings.connections.get(index).map(|conn| &conn.stats)
+    }
+
     pub async fn remove_connection(&mut self, index: usize) -> Result<AppSettings, Box<dyn std::error::Error>> {
         if index >= self.current_settings.connections.len() {
             return Err("Connection index out of bounds".into());
         }
 
-        self.current_settings.connections.remove(index);
+        let removed = self.current_settings.connections.remove(index);
+        self.forget_secret(&removed.name);
+        self.current_settings.connection_history.remove(&removed.name);
         self.save_settings().await?;
         Ok(self.current_settings.clone())
     }
-}
\ No newline at end of file
+
+    fn history_for(&mut self, connection_name: &str) -> Result<&mut ConnectionHistory, Box<dyn std::error::Error>> {
+        if !self.current_settings.connections.iter().any(|conn| conn.name == connection_name) {
+            return Err(format!("Connection '{}' not found", connection_name).into());
+        }
+        Ok(self
+            .current_settings
+            .connection_history
+            .entry(connection_name.to_string())
+            .or_default())
+    }
+
+    pub async fn add_favorite(&mut self, connection_name: &str, path: String) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        let history = self.history_for(connection_name)?;
+        if !history.favorites.contains(&path) {
+            history.favorites.push(path);
+        }
+        self.save_settings().await?;
+        Ok(self.current_settings.clone())
+    }
+
+    pub async fn remove_favorite(&mut self, connection_name: &str, path: &str) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        let history = self.history_for(connection_name)?;
+        history.favorites.retain(|favorite| favorite != path);
+        self.save_settings().await?;
+        Ok(self.current_settings.clone())
+    }
+
+    // Moves the location to the front of the recent list, dropping the oldest entries past the cap
+    pub async fn record_recent_path(&mut self, connection_name: &str, bucket: String, prefix: String) -> Result<AppSettings, Box<dyn std::error::Error>> {
+        let history = self.history_for(connection_name)?;
+        history.recent_paths.retain(|recent| recent.bucket != bucket || recent.prefix != prefix);
+        history.recent_paths.insert(0, RecentPath {
+            bucket,
+            prefix,
+            accessed_at: Utc::now(),
+        });
+        history.recent_paths.truncate(MAX_RECENT_PATHS);
+        self.save_settings().await?;
+        Ok(self.current_settings.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_settings_path() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("bucketviewer-settings-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir.join("settings.json")
+    }
+
+    fn connection(name: &str) -> ConnectionConfig {
+        serde_json::from_value(serde_json::json!({
+            "name": name,
+            "service_type": "minio",
+            "endpoint": "http://localhost:9000",
+            "access_key": "minioadmin",
+            "secret_key": "minioadmin",
+            "region": "us-east-1",
+            "is_default": false,
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn load_recovers_truncated_file_from_backup() {
+        let settings_path = temp_settings_path();
+        let mut backup = AppSettings::default();
+        backup.general.refresh_interval = 42;
+        let content = serde_json::to_string_pretty(&backup).unwrap();
+        std::fs::write(settings_path.with_extension("json.bak"), &content).unwrap();
+        std::fs::write(&settings_path, &content[..content.len() / 2]).unwrap();
+
+        let mut manager = SettingsManager {
+            settings_path: settings_path.clone(),
+            current_settings: AppSettings::default(),
+            keychain_secrets: HashMap::new(),
+            stats_dirty: false,
+            bandwidth: BandwidthState::default(),
+        };
+        let loaded = manager.load_settings().await.unwrap();
+
+        assert_eq!(loaded.general.refresh_interval, 42);
+        assert!(settings_path.with_extension("json.corrupt").exists());
+        let rewritten = std::fs::read_to_string(&settings_path).unwrap();
+        assert_eq!(parse_settings(&rewritten).unwrap().0.general.refresh_interval, 42);
+        std::fs::remove_dir_all(settings_path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reset_forgets_the_secrets_of_dropped_connections() {
+        let settings_path = temp_settings_path();
+        let mut current_settings = AppSettings::default();
+        current_settings.connections.push(connection("old"));
+        let mut manager = SettingsManager {
+            settings_path: settings_path.clone(),
+            current_settings,
+            keychain_secrets: HashMap::from([
+                ("old".to_string(), "secret".to_string()),
+                (session_token_account("old"), "token".to_string()),
+            ]),
+            stats_dirty: false,
+            bandwidth: BandwidthState::default(),
+        };
+
+        let reset = manager.reset_to_defaults().await.unwrap();
+
+        assert!(reset.connections.is_empty());
+        assert!(manager.keychain_secrets.is_empty());
+        std::fs::remove_dir_all(settings_path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn update_settings_validates_connections() {
+        let settings_path = temp_settings_path();
+        let mut manager = SettingsManager {
+            settings_path: settings_path.clone(),
+            current_settings: AppSettings::default(),
+            keychain_secrets: HashMap::new(),
+            stats_dirty: false,
+            bandwidth: BandwidthState::default(),
+        };
+
+        let duplicates = AppSettings {
+            connections: vec![connection("minio"), connection("minio")],
+            ..AppSettings::default()
+        };
+        let err = manager.update_settings(duplicates).await.unwrap_err();
+        assert_eq!(err.to_string(), "A connection named 'minio' already exists");
+
+        let unnamed = AppSettings {
+            connections: vec![connection(" ")],
+            ..AppSettings::default()
+        };
+        assert!(manager.update_settings(unnamed).await.is_err());
+
+        let no_endpoint = AppSettings {
+            connections: vec![ConnectionConfig { endpoint: String::new(), service_type: "Custom".to_string(), ..connection("minio") }],
+            ..AppSettings::default()
+        };
+        assert!(manager.update_settings(no_endpoint).await.is_err());
+        assert!(!settings_path.exists());
+        std::fs::remove_dir_all(settings_path.parent().unwrap()).unwrap();
+    }
+
+    #[tokio::test]
+    async fn connection_attempts_wait_for_the_next_save() {
+        let settings_path = temp_settings_path();
+        let mut manager = SettingsManager {
+            settings_path: settings_path.clone(),
+            current_settings: AppSettings {
+                connections: vec![connection("minio")],
+                ..AppSettings::default()
+            },
+            keychain_secrets: HashMap::new(),
+            stats_dirty: false,
+            bandwidth: BandwidthState::default(),
+        };
+
+        manager.record_connection_attempt("minio", Ok(()));
+        manager.record_connection_attempt("minio", Err("timed out".to_string()));
+        assert!(!settings_path.exists());
+        assert_eq!(manager.connection_stats(0).unwrap().failure_count, 1);
+
+        manager.flush_stats().await.unwrap();
+        let saved = parse_settings(&std::fs::read_to_string(&settings_path).unwrap()).unwrap().0;
+        assert_eq!(saved.connections[0].stats.connect_count, 1);
+        assert_eq!(saved.connections[0].stats.last_error.as_deref(), Some("timed out"));
+
+        // Nothing new to save, so the backup isn't rotated
+        manager.flush_stats().await.unwrap();
+        assert!(!settings_path.with_extension("json.bak").exists());
+        std::fs::remove_dir_all(settings_path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn rejects_a_connection_limit_of_zero() {
+        let zero = ConnectionConfig { max_connections: Some(0), ..connection("minio") };
+        assert_eq!(
+            SettingsManager::validate_connection(&[], &zero, None).unwrap_err(),
+            "The connection limit must be at least 1"
+        );
+
+        let one = ConnectionConfig { max_connections: Some(1), ..connection("minio") };
+        assert!(SettingsManager::validate_connection(&[], &one, None).is_ok());
+    }
+
+    #[test]
+    fn migrates_1_0_settings() {
+        let (settings, migrated) = parse_settings(include_str!("../tests/fixtures/settings-v1.0.0.json")).unwrap();
+
+        assert!(migrated);
+        assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+        assert_eq!(settings.general.refresh_interval, 60);
+        assert_eq!(settings.general.log_level, logging::DEFAULT_LOG_LEVEL);
+        assert_eq!(settings.appearance.theme, "dark");
+        assert!(settings.connection_history.is_empty());
+
+        let connection = &settings.connections[0];
+        assert_eq!(connection.name, "minio");
+        assert_eq!(connection.secret_key, "minioadmin");
+        assert!(connection.is_default);
+        assert_eq!(connection.force_path_style, None);
+        assert_eq!(connection.session_token, None);
+        assert_eq!(connection.proxy_url, None);
+        assert!(!connection.allow_invalid_certs);
+    }
+
+    #[test]
+    fn migrates_settings_without_a_version() {
+        let (settings, migrated) = parse_settings(include_str!("../tests/fixtures/settings-unversioned.json")).unwrap();
+
+        assert!(migrated);
+        assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+        assert_eq!(settings.connections[0].name, "aws");
+        assert_eq!(settings.connections[0].timeout_secs, None);
+    }
+
+    #[test]
+    fn current_settings_are_not_migrated() {
+        let content = serde_json::to_string(&AppSettings::default()).unwrap();
+        let (settings, migrated) = parse_settings(&content).unwrap();
+
+        assert!(!migrated);
+        assert_eq!(settings.version, CURRENT_SETTINGS_VERSION);
+    }
+}
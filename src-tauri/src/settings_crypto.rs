@@ -0,0 +1,151 @@
+use aes_gcm::aead::{Aead, KeyInit, OsRng};
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::{Aes256Gcm, Nonce};
+use argon2::{Algorithm, Argon2, Params, Version};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+const ENVELOPE_FORMAT: &str = "bucketviewer-encrypted-settings";
+const ENVELOPE_VERSION: u32 = 1;
+const SALT_LENGTH: usize = 16;
+const NONCE_LENGTH: usize = 12;
+// Upper bounds for KDF parameters read from a file, so a crafted export can't make
+// the import allocate gigabytes or spin for minutes. Well above what encrypt writes.
+const MAX_MEMORY_KIB: u32 = 256 * 1024;
+const MAX_ITERATIONS: u32 = 16;
+const MAX_PARALLELISM: u32 = 8;
+
+// Written instead of the plain settings JSON when an export is password protected.
+// The KDF parameters are stored so they can be raised later without breaking old exports.
+#[derive(Debug, Serialize, Deserialize)]
+struct EncryptedEnvelope {
+    format: String,
+    version: u32,
+    kdf: String,
+    memory_kib: u32,
+    iterations: u32,
+    parallelism: u32,
+    salt: String,
+    nonce: String,
+    ciphertext: String,
+}
+
+pub fn is_encrypted(content: &str) -> bool {
+    serde_json::from_str::<EncryptedEnvelope>(content)
+        .map(|envelope| envelope.format == ENVELOPE_FORMAT)
+        .unwrap_or(false)
+}
+
+// Key derivation is deliberately slow, so it runs on the blocking pool
+pub async fn encrypt(plaintext: String, password: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || encrypt_blocking(&plaintext, &password))
+        .await
+        .map_err(|e| format!("Failed to encrypt settings: {}", e))?
+}
+
+pub async fn decrypt(content: String, password: String) -> Result<String, String> {
+    tokio::task::spawn_blocking(move || decrypt_blocking(&content, &password))
+        .await
+        .map_err(|e| format!("Failed to decrypt settings: {}", e))?
+}
+
+fn encrypt_blocking(plaintext: &str, password: &str) -> Result<String, String> {
+    if password.is_empty() {
+        return Err("Password cannot be empty".to_string());
+    }
+
+    let params = Params::default();
+    let mut salt = [0u8; SALT_LENGTH];
+    OsRng.fill_bytes(&mut salt);
+    let mut nonce = [0u8; NONCE_LENGTH];
+    OsRng.fill_bytes(&mut nonce);
+
+    let cipher = cipher_for(password, &salt, params.clone())?;
+    let ciphertext = cipher
+        .encrypt(&Nonce::from(nonce), plaintext.as_bytes())
+        .map_err(|_| "Failed to encrypt settings".to_string())?;
+
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let envelope = EncryptedEnvelope {
+        format: ENVELOPE_FORMAT.to_string(),
+        version: ENVELOPE_VERSION,
+        kdf: "argon2id".to_string(),
+        memory_kib: params.m_cost(),
+        iterations: params.t_cost(),
+        parallelism: params.p_cost(),
+        salt: b64.encode(salt),
+        nonce: b64.encode(nonce),
+        ciphertext: b64.encode(ciphertext),
+    };
+    serde_json::to_string_pretty(&envelope).map_err(|e| e.to_string())
+}
+
+fn decrypt_blocking(content: &str, password: &str) -> Result<String, String> {
+    let envelope: EncryptedEnvelope =
+        serde_json::from_str(content).map_err(|e| format!("Invalid encrypted settings file: {}", e))?;
+    if envelope.format != ENVELOPE_FORMAT || envelope.version != ENVELOPE_VERSION || envelope.kdf != "argon2id" {
+        return Err(format!(
+            "Unsupported encrypted settings format {} v{} ({})",
+            envelope.format, envelope.version, envelope.kdf
+        ));
+    }
+
+    let b64 = base64::engine::general_purpose::STANDARD;
+    let decode = |field: &str, value: &str| b64.decode(value).map_err(|e| format!("Invalid {} in encrypted settings: {}", field, e));
+    let salt = decode("salt", &envelope.salt)?;
+    let nonce: [u8; NONCE_LENGTH] = decode("nonce", &envelope.nonce)?
+        .try_into()
+        .map_err(|_| "Invalid nonce in encrypted settings".to_string())?;
+    let ciphertext = decode("ciphertext", &envelope.ciphertext)?;
+
+    if envelope.memory_kib > MAX_MEMORY_KIB
+        || envelope.iterations > MAX_ITERATIONS
+        || envelope.parallelism > MAX_PARALLELISM
+    {
+        return Err(format!(
+            "Key derivation parameters are too expensive (memory {} KiB, {} iterations, parallelism {})",
+            envelope.memory_kib, envelope.iterations, envelope.parallelism
+        ));
+    }
+    let params = Params::new(envelope.memory_kib, envelope.iterations, envelope.parallelism, None)
+        .map_err(|e| format!("Invalid key derivation parameters: {}", e))?;
+    let cipher = cipher_for(password, &salt, params)?;
+    let plaintext = cipher
+        .decrypt(&Nonce::from(nonce), ciphertext.as_ref())
+        // AES-GCM can't tell a wrong password from a tampered file
+        .map_err(|_| "Wrong password or corrupted settings file".to_string())?;
+
+    String::from_utf8(plaintext).map_err(|_| "Decrypted settings are not valid UTF-8".to_string())
+}
+
+fn cipher_for(password: &str, salt: &[u8], params: Params) -> Result<Aes256Gcm, String> {
+    let mut key = [0u8; 32];
+    Argon2::new(Algorithm::Argon2id, Version::V0x13, params)
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("Failed to derive key from password: {}", e))?;
+    Aes256Gcm::new_from_slice(&key).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn round_trips_with_the_right_password() {
+        let encrypted = encrypt("{\"a\":1}".to_string(), "secret".to_string()).await.unwrap();
+        assert!(is_encrypted(&encrypted));
+        assert_eq!(decrypt(encrypted.clone(), "secret".to_string()).await.unwrap(), "{\"a\":1}");
+        assert!(decrypt(encrypted, "wrong".to_string()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn rejects_expensive_kdf_parameters() {
+        let encrypted = encrypt("{}".to_string(), "secret".to_string()).await.unwrap();
+        let mut envelope: EncryptedEnvelope = serde_json::from_str(&encrypted).unwrap();
+        envelope.memory_kib = 4 * 1024 * 1024;
+        let crafted = serde_json::to_string(&envelope).unwrap();
+
+        let err = decrypt(crafted, "secret".to_string()).await.unwrap_err();
+        assert!(err.contains("too expensive"));
+    }
+}
@@ -0,0 +1,111 @@
+use base64::Engine;
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat, ImageReader, Limits};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::io::Cursor;
+use std::sync::Mutex;
+
+pub const DEFAULT_THUMBNAIL_DIMENSION: u32 = 256;
+pub const MAX_THUMBNAIL_DIMENSION: u32 = 1024;
+// Larger objects aren't downloaded just to be shrunk
+pub const MAX_THUMBNAIL_SOURCE_BYTES: u64 = 32 * 1024 * 1024;
+// Guards against small files that decode to enormous images
+const MAX_SOURCE_PIXELS_PER_SIDE: u32 = 16_384;
+const JPEG_QUALITY: u8 = 80;
+const CACHE_CAPACITY: usize = 256;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImageThumbnail {
+    // Base64 PNG for images with transparency, JPEG otherwise
+    pub data: String,
+    pub content_type: String,
+    pub width: u32,
+    pub height: u32,
+}
+
+// Decodes an image and shrinks it to fit within `max_dimension` on both sides,
+// keeping the aspect ratio. Images that already fit are re-encoded at full size.
+pub fn render_thumbnail(bytes: &[u8], max_dimension: u32) -> Result<ImageThumbnail, String> {
+    let mut reader = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()
+        .map_err(|e| format!("Failed to read image: {}", e))?;
+    if reader.format().is_none() {
+        return Err("Object is not a supported image".to_string());
+    }
+    let mut limits = Limits::default();
+    limits.max_image_width = Some(MAX_SOURCE_PIXELS_PER_SIDE);
+    limits.max_image_height = Some(MAX_SOURCE_PIXELS_PER_SIDE);
+    reader.limits(limits);
+
+    let image = reader.decode().map_err(|e| format!("Failed to decode image: {}", e))?;
+    let image = if image.width() > max_dimension || image.height() > max_dimension {
+        image.thumbnail(max_dimension, max_dimension)
+    } else {
+        image
+    };
+
+    let mut data = Vec::new();
+    let content_type = if image.color().has_alpha() {
+        image
+            .write_to(&mut Cursor::new(&mut data), ImageFormat::Png)
+            .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+        "image/png"
+    } else {
+        JpegEncoder::new_with_quality(&mut data, JPEG_QUALITY)
+            .encode_image(&DynamicImage::ImageRgb8(image.to_rgb8()))
+            .map_err(|e| format!("Failed to encode thumbnail: {}", e))?;
+        "image/jpeg"
+    };
+
+    Ok(ImageThumbnail {
+        data: base64::engine::general_purpose::STANDARD.encode(&data),
+        content_type: content_type.to_string(),
+        width: image.width(),
+        height: image.height(),
+    })
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ThumbnailKey {
+    bucket: String,
+    key: String,
+    etag: String,
+    max_dimension: u32,
+}
+
+// Recently rendered thumbnails for one connection. Keying on the ETag means an
+// overwritten object never gets a stale thumbnail.
+#[derive(Default)]
+pub struct ThumbnailCache {
+    entries: Mutex<VecDeque<(ThumbnailKey, ImageThumbnail)>>,
+}
+
+impl ThumbnailCache {
+    pub fn get(&self, bucket: &str, key: &str, etag: &str, max_dimension: u32) -> Option<ImageThumbnail> {
+        let mut entries = self.entries.lock().unwrap();
+        let index = entries.iter().position(|(cached, _)| {
+            cached.bucket == bucket && cached.key == key && cached.etag == etag && cached.max_dimension == max_dimension
+        })?;
+        // Move to the back so the least recently used entry is evicted first
+        let entry = entries.remove(index)?;
+        let thumbnail = entry.1.clone();
+        entries.push_back(entry);
+        Some(thumbnail)
+    }
+
+    pub fn insert(&self, bucket: &str, key: &str, etag: &str, max_dimension: u32, thumbnail: ImageThumbnail) {
+        let cache_key = ThumbnailKey {
+            bucket: bucket.to_string(),
+            key: key.to_string(),
+            etag: etag.to_string(),
+            max_dimension,
+        };
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|(cached, _)| *cached != cache_key);
+        if entries.len() >= CACHE_CAPACITY {
+            entries.pop_front();
+        }
+        entries.push_back((cache_key, thumbnail));
+    }
+}
@@ -0,0 +1,287 @@
+use crate::s3_service::ObjectInfo;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{Emitter, Window};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+pub const TRANSFER_PROGRESS_EVENT: &str = "transfer-progress";
+pub const TRANSFER_COMPLETE_EVENT: &str = "transfer-complete";
+pub const TRANSFER_ERROR_EVENT: &str = "transfer-error";
+pub const PREFIX_STATS_PROGRESS_EVENT: &str = "prefix-stats-progress";
+pub const SEARCH_PROGRESS_EVENT: &str = "search-progress";
+pub const EMPTY_BUCKET_PROGRESS_EVENT: &str = "empty-bucket-progress";
+pub const SELECT_RECORDS_EVENT: &str = "select-records";
+
+const PROGRESS_EMIT_INTERVAL: Duration = Duration::from_millis(250);
+pub const DEFAULT_MAX_CONCURRENT_TRANSFERS: u32 = 4;
+pub const MAX_CONCURRENT_TRANSFERS: u32 = 32;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferProgress {
+    pub id: String,
+    pub key: String,
+    pub bytes_transferred: u64,
+    pub total_bytes: u64,
+    pub rate_bytes_per_sec: f64,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferFailure {
+    pub id: String,
+    pub key: String,
+    pub error: String,
+}
+
+// Matches found in one listing page of a running search, identified by its client-generated id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchProgress {
+    pub id: String,
+    pub matches: Vec<ObjectInfo>,
+    pub scanned: u64,
+}
+
+// Whole result records of a streaming S3 Select query, identified by its client-generated id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SelectRecords {
+    pub id: String,
+    pub records: String,
+}
+
+// Emits progress events for a single transfer, throttled so fast links
+// don't flood the event bus.
+pub struct ProgressReporter {
+    window: Window,
+    id: String,
+    key: String,
+    started: Instant,
+    bytes_transferred: AtomicU64,
+    last_emit: Mutex<Option<Instant>>,
+}
+
+impl ProgressReporter {
+    pub fn new(window: Window, id: String, key: String) -> Self {
+        Self {
+            window,
+            id,
+            key,
+            started: Instant::now(),
+            bytes_transferred: AtomicU64::new(0),
+            last_emit: Mutex::new(None),
+        }
+    }
+
+    pub fn update(&self, bytes_transferred: u64, total_bytes: u64) {
+        self.bytes_transferred.store(bytes_transferred, Ordering::Relaxed);
+
+        let now = Instant::now();
+        {
+            let mut last_emit = self.last_emit.lock().unwrap();
+            if let Some(last) = *last_emit {
+                if now.duration_since(last) < PROGRESS_EMIT_INTERVAL {
+                    return;
+                }
+            }
+            *last_emit = Some(now);
+        }
+
+        let progress = self.progress(bytes_transferred, total_bytes);
+        if let Err(e) = self.window.emit(TRANSFER_PROGRESS_EVENT, progress) {
+            warn!("Failed to emit transfer progress for {}: {}", self.id, e);
+        }
+    }
+
+    pub fn complete(&self) {
+        let bytes_transferred = self.bytes_transferred.load(Ordering::Relaxed);
+        let progress = self.progress(bytes_transferred, bytes_transferred);
+        if let Err(e) = self.window.emit(TRANSFER_COMPLETE_EVENT, progress) {
+            warn!("Failed to emit transfer completion for {}: {}", self.id, e);
+        }
+    }
+
+    pub fn error(&self, error: &str) {
+        let failure = TransferFailure {
+            id: self.id.clone(),
+            key: self.key.clone(),
+            error: error.to_string(),
+        };
+        if let Err(e) = self.window.emit(TRANSFER_ERROR_EVENT, failure) {
+            warn!("Failed to emit transfer error for {}: {}", self.id, e);
+        }
+    }
+
+    fn progress(&self, bytes_transferred: u64, total_bytes: u64) -> TransferProgress {
+        let elapsed = self.started.elapsed().as_secs_f64();
+        TransferProgress {
+            id: self.id.clone(),
+            key: self.key.clone(),
+            bytes_transferred,
+            total_bytes,
+            rate_bytes_per_sec: if elapsed > 0.0 { bytes_transferred as f64 / elapsed } else { 0.0 },
+        }
+    }
+}
+
+// Tracks the cancellation token of every in-flight transfer by its client-generated id
+pub struct CancellationRegistry {
+    tokens: Mutex<HashMap<String, CancellationToken>>,
+}
+
+impl CancellationRegistry {
+    pub fn new() -> Self {
+        Self {
+            tokens: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub fn register(&self, transfer_id: &str) -> CancellationToken {
+        let token = CancellationToken::new();
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.insert(transfer_id.to_string(), token.clone());
+        token
+    }
+
+    pub fn cancel(&self, transfer_id: &str) -> bool {
+        let tokens = self.tokens.lock().unwrap();
+        match tokens.get(transfer_id) {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn remove(&self, transfer_id: &str) {
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.remove(transfer_id);
+    }
+}
+
+impl Default for CancellationRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn validate_max_concurrent_transfers(limit: u32) -> Result<(), String> {
+    if (1..=MAX_CONCURRENT_TRANSFERS).contains(&limit) {
+        Ok(())
+    } else {
+        Err(format!(
+            "Concurrent transfers must be between 1 and {}, got {}",
+            MAX_CONCURRENT_TRANSFERS, limit
+        ))
+    }
+}
+
+// Caps how many uploads and downloads run at once across the whole app. Every transfer
+// holds a permit for its duration; the rest queue in FIFO order.
+pub struct TransferScheduler {
+    semaphore: Arc<Semaphore>,
+    limits: Arc<Mutex<SchedulerLimits>>,
+}
+
+struct SchedulerLimits {
+    limit: u32,
+    // Permits still to be taken out of circulation after the limit was lowered
+    owed: u32,
+}
+
+// A transfer slot. Dropping it frees the slot, unless the limit was lowered meanwhile.
+pub struct TransferPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    limits: Arc<Mutex<SchedulerLimits>>,
+}
+
+impl Drop for TransferPermit {
+    fn drop(&mut self) {
+        let mut limits = self.limits.lock().unwrap();
+        if let Some(permit) = self.permit.take() {
+            if limits.owed > 0 {
+                limits.owed -= 1;
+                permit.forget();
+            }
+        }
+    }
+}
+
+impl TransferScheduler {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(limit as usize)),
+            limits: Arc::new(Mutex::new(SchedulerLimits { limit, owed: 0 })),
+        }
+    }
+
+    // Waits for a free slot; None if the transfer was cancelled while queued
+    pub async fn acquire(&self, cancel_token: &CancellationToken) -> Option<TransferPermit> {
+        let permit = tokio::select! {
+            _ = cancel_token.cancelled() => None,
+            permit = Arc::clone(&self.semaphore).acquire_owned() => permit.ok(),
+        }?;
+        Some(TransferPermit { permit: Some(permit), limits: Arc::clone(&self.limits) })
+    }
+
+    // Running transfers are never interrupted: lowering the limit takes effect as they
+    // finish, and raising it again first cancels whatever reduction is still owed
+    pub fn set_limit(&self, limit: u32) {
+        let mut limits = self.limits.lock().unwrap();
+        if limit > limits.limit {
+            let raise = limit - limits.limit;
+            let repaid = raise.min(limits.owed);
+            limits.owed -= repaid;
+            self.semaphore.add_permits((raise - repaid) as usize);
+        } else {
+            limits.owed += limits.limit - limit;
+            while limits.owed > 0 {
+                let Ok(permit) = self.semaphore.try_acquire() else {
+                    break;
+                };
+                permit.forget();
+                limits.owed -= 1;
+            }
+        }
+        limits.limit = limit;
+    }
+}
+
+impl Default for TransferScheduler {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_CONCURRENT_TRANSFERS)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn lowering_then_raising_the_limit_keeps_the_new_limit() {
+        let scheduler = TransferScheduler::new(3);
+        let token = CancellationToken::new();
+        let running: Vec<_> = futures::future::join_all((0..3).map(|_| scheduler.acquire(&token))).await;
+
+        scheduler.set_limit(1);
+        scheduler.set_limit(2);
+        drop(running);
+
+        assert_eq!(scheduler.semaphore.available_permits(), 2);
+    }
+
+    #[tokio::test]
+    async fn lowering_the_limit_waits_for_running_transfers() {
+        let scheduler = TransferScheduler::new(2);
+        let token = CancellationToken::new();
+        let first = scheduler.acquire(&token).await;
+
+        scheduler.set_limit(1);
+        assert_eq!(scheduler.semaphore.available_permits(), 0);
+        drop(first);
+        assert_eq!(scheduler.semaphore.available_permits(), 1);
+    }
+}
@@ -0,0 +1,366 @@
+use crate::command_error::{CommandError, ErrorCode};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{Emitter, Window};
+use tokio_util::sync::CancellationToken;
+use tracing::warn;
+
+pub const TRANSFER_STATUS_EVENT: &str = "transfer-status";
+
+pub type TransferQueueState = Arc<TransferQueue>;
+
+// Completed and cancelled jobs kept for the transfer list; older ones are dropped
+const MAX_FINISHED_JOBS: usize = 200;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferKind {
+    Upload,
+    Download,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransferStatus {
+    Queued,
+    Running,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferJob {
+    pub id: String,
+    pub kind: TransferKind,
+    pub connection_name: String,
+    pub bucket: String,
+    pub key: String,
+    pub local_path: String,
+    pub status: TransferStatus,
+    pub bytes_done: u64,
+    pub total: u64,
+    #[serde(default)]
+    pub error: Option<String>,
+    #[serde(default)]
+    pub error_code: Option<ErrorCode>,
+    // Multipart upload to continue instead of starting over
+    #[serde(default)]
+    pub upload_id: Option<String>,
+    // Whether a download may replace an existing local file
+    #[serde(default)]
+    pub overwrite: bool,
+}
+
+impl TransferJob {
+    pub fn new(
+        kind: TransferKind,
+        connection_name: String,
+        bucket: String,
+        key: String,
+        local_path: String,
+        overwrite: bool,
+    ) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            connection_name,
+            bucket,
+            key,
+            local_path,
+            status: TransferStatus::Queued,
+            bytes_done: 0,
+            total: 0,
+            error: None,
+            error_code: None,
+            upload_id: None,
+            overwrite,
+        }
+    }
+}
+
+struct QueueEntry {
+    job: TransferJob,
+    // Set while the job is queued or running
+    cancel_token: Option<CancellationToken>,
+}
+
+// Every queued transfer in the order it was added, persisted so jobs that were
+// interrupted by a restart can be resumed
+pub struct TransferQueue {
+    path: PathBuf,
+    entries: Mutex<Vec<QueueEntry>>,
+    // Bumped by every save; the file only ever moves forward to a newer snapshot
+    generation: AtomicU64,
+    saved_generation: Arc<Mutex<u64>>,
+}
+
+impl TransferQueue {
+    // Jobs that were queued or running when the app exited come back paused
+    pub fn load(path: PathBuf) -> Self {
+        if let Some(parent) = path.parent() {
+            if let Err(e) = std::fs::create_dir_all(parent) {
+                warn!("Failed to create {}: {}", parent.display(), e);
+            }
+        }
+        let jobs: Vec<TransferJob> = match std::fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                warn!("Ignoring unreadable transfer queue {}: {}", path.display(), e);
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+
+        let mut entries = jobs
+            .into_iter()
+            .map(|mut job| {
+                if matches!(job.status, TransferStatus::Queued | TransferStatus::Running) {
+                    job.status = TransferStatus::Paused;
+                }
+                QueueEntry { job, cancel_token: None }
+            })
+            .collect();
+        prune_finished(&mut entries);
+
+        Self {
+            path,
+            entries: Mutex::new(entries),
+            generation: AtomicU64::new(0),
+            saved_generation: Arc::new(Mutex::new(0)),
+        }
+    }
+
+    pub fn list(&self) -> Vec<TransferJob> {
+        self.entries.lock().unwrap().iter().map(|entry| entry.job.clone()).collect()
+    }
+
+    pub fn get(&self, id: &str) -> Option<TransferJob> {
+        self.entries.lock().unwrap().iter().find(|entry| entry.job.id == id).map(|entry| entry.job.clone())
+    }
+
+    pub fn enqueue(&self, job: TransferJob) -> CancellationToken {
+        let cancel_token = CancellationToken::new();
+        {
+            let mut entries = self.entries.lock().unwrap();
+            entries.push(QueueEntry {
+                job,
+                cancel_token: Some(cancel_token.clone()),
+            });
+            prune_finished(&mut entries);
+        }
+        self.save();
+        cancel_token
+    }
+
+    // Queues a job again if its status is one of `from`. A fresh start also forgets
+    // any partial progress.
+    pub fn requeue(
+        &self,
+        id: &str,
+        from: &[TransferStatus],
+        fresh_start: bool,
+    ) -> Result<(TransferJob, CancellationToken), String> {
+        let result = {
+            let mut entries = self.entries.lock().unwrap();
+            let entry = entries
+                .iter_mut()
+                .find(|entry| entry.job.id == id)
+                .ok_or_else(|| format!("Transfer {} not found", id))?;
+            if !from.contains(&entry.job.status) {
+                return Err(format!("Transfer {} is {:?}", id, entry.job.status));
+            }
+            if entry.cancel_token.is_some() {
+                return Err(format!("Transfer {} is still stopping", id));
+            }
+
+            let cancel_token = CancellationToken::new();
+            entry.cancel_token = Some(cancel_token.clone());
+            entry.job.status = TransferStatus::Queued;
+            entry.job.error = None;
+            entry.job.error_code = None;
+            if fresh_start {
+                entry.job.bytes_done = 0;
+                entry.job.upload_id = None;
+            }
+            (entry.job.clone(), cancel_token)
+        };
+        self.save();
+        Ok(result)
+    }
+
+    // Stops a queued or running job, keeping its progress
+    pub fn pause(&self, id: &str) -> Option<TransferJob> {
+        self.stop(id, TransferStatus::Paused, &[TransferStatus::Queued, TransferStatus::Running])
+            .map(|(job, _)| job)
+    }
+
+    // Returns the cancelled job and whether a running task is still winding it down
+    pub fn cancel(&self, id: &str) -> Option<(TransferJob, bool)> {
+        self.stop(
+            id,
+            TransferStatus::Cancelled,
+            &[TransferStatus::Queued, TransferStatus::Running, TransferStatus::Paused, TransferStatus::Failed],
+        )
+    }
+
+    fn stop(&self, id: &str, status: TransferStatus, from: &[TransferStatus]) -> Option<(TransferJob, bool)> {
+        let result = {
+            let mut entries = self.entries.lock().unwrap();
+            let entry = entries.iter_mut().find(|entry| entry.job.id == id && from.contains(&entry.job.status))?;
+            if let Some(cancel_token) = &entry.cancel_token {
+                cancel_token.cancel();
+            }
+            entry.job.status = status;
+            (entry.job.clone(), entry.cancel_token.is_some())
+        };
+        self.save();
+        Some(result)
+    }
+
+    // Moves a job that waited for a transfer slot to running; None if it was paused
+    // or cancelled in the meantime
+    pub fn start(&self, id: &str) -> Option<TransferJob> {
+        self.update(id, |job| {
+            if job.status == TransferStatus::Queued {
+                job.status = TransferStatus::Running;
+            }
+        })
+        .filter(|job| job.status == TransferStatus::Running)
+    }
+
+    pub fn update_progress(&self, id: &str, bytes_done: u64, total: u64) {
+        if let Some(entry) = self.entries.lock().unwrap().iter_mut().find(|entry| entry.job.id == id) {
+            entry.job.bytes_done = bytes_done;
+            entry.job.total = total;
+        }
+    }
+
+    // Applies a change to a job and persists the queue
+    pub fn update(&self, id: &str, change: impl FnOnce(&mut TransferJob)) -> Option<TransferJob> {
+        let job = {
+            let mut entries = self.entries.lock().unwrap();
+            let entry = entries.iter_mut().find(|entry| entry.job.id == id)?;
+            change(&mut entry.job);
+            entry.job.clone()
+        };
+        self.save();
+        Some(job)
+    }
+
+    // Records how a run ended. A job that was paused or cancelled meanwhile keeps that status.
+    pub fn finish(&self, id: &str, result: Result<(), CommandError>) -> Option<TransferJob> {
+        let job = {
+            let mut entries = self.entries.lock().unwrap();
+            let entry = entries.iter_mut().find(|entry| entry.job.id == id)?;
+            entry.cancel_token = None;
+            if entry.job.status == TransferStatus::Running {
+                match result {
+                    Ok(()) => {
+                        entry.job.status = TransferStatus::Completed;
+                        entry.job.bytes_done = entry.job.total;
+                        entry.job.upload_id = None;
+                    }
+                    Err(error) => {
+                        entry.job.status = TransferStatus::Failed;
+                        entry.job.error = Some(error.message);
+                        entry.job.error_code = Some(error.code);
+                    }
+                }
+            }
+            entry.job.clone()
+        };
+        self.save();
+        Some(job)
+    }
+
+    // Writes a snapshot of the queue on the blocking pool. Snapshots can reach the pool
+    // out of order, so one older than what's already on disk is dropped.
+    fn save(&self) {
+        let (generation, content) = {
+            let entries = self.entries.lock().unwrap();
+            let jobs: Vec<&TransferJob> = entries.iter().map(|entry| &entry.job).collect();
+            let content = match serde_json::to_string_pretty(&jobs) {
+                Ok(content) => content,
+                Err(e) => {
+                    warn!("Failed to serialize transfer queue: {}", e);
+                    return;
+                }
+            };
+            (self.generation.fetch_add(1, Ordering::SeqCst) + 1, content)
+        };
+
+        let path = self.path.clone();
+        let saved_generation = Arc::clone(&self.saved_generation);
+        tauri::async_runtime::spawn_blocking(move || {
+            let mut saved_generation = saved_generation.lock().unwrap();
+            if *saved_generation > generation {
+                return;
+            }
+            let temp_path = path.with_extension("json.tmp");
+            match std::fs::write(&temp_path, content).and_then(|_| std::fs::rename(&temp_path, &path)) {
+                Ok(()) => *saved_generation = generation,
+                Err(e) => warn!("Failed to save transfer queue to {}: {}", path.display(), e),
+            }
+        });
+    }
+}
+
+// Drops the oldest completed and cancelled jobs beyond MAX_FINISHED_JOBS. Failed jobs
+// stay until they are retried or cancelled, since they may have partial data to clean up.
+fn prune_finished(entries: &mut Vec<QueueEntry>) {
+    let is_finished = |entry: &QueueEntry| {
+        entry.cancel_token.is_none() && matches!(entry.job.status, TransferStatus::Completed | TransferStatus::Cancelled)
+    };
+    let mut surplus = entries.iter().filter(|entry| is_finished(entry)).count().saturating_sub(MAX_FINISHED_JOBS);
+    entries.retain(|entry| {
+        if surplus > 0 && is_finished(entry) {
+            surplus -= 1;
+            false
+        } else {
+            true
+        }
+    });
+}
+
+pub fn emit_status(window: &Window, job: &TransferJob) {
+    if let Err(e) = window.emit(TRANSFER_STATUS_EVENT, job) {
+        warn!("Failed to emit status of transfer {}: {}", job.id, e);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(status: TransferStatus) -> QueueEntry {
+        let mut job = TransferJob::new(
+            TransferKind::Download,
+            "test".to_string(),
+            "bucket".to_string(),
+            "key".to_string(),
+            "/tmp/key".to_string(),
+            false,
+        );
+        job.status = status;
+        QueueEntry { job, cancel_token: None }
+    }
+
+    #[test]
+    fn prune_drops_the_oldest_finished_jobs_only() {
+        let mut entries = vec![entry(TransferStatus::Failed), entry(TransferStatus::Paused)];
+        entries.extend((0..MAX_FINISHED_JOBS + 5).map(|i| {
+            entry(if i % 2 == 0 { TransferStatus::Completed } else { TransferStatus::Cancelled })
+        }));
+        let newest = entries.last().unwrap().job.id.clone();
+
+        prune_finished(&mut entries);
+
+        assert_eq!(entries.len(), MAX_FINISHED_JOBS + 2);
+        assert_eq!(entries[0].job.status, TransferStatus::Failed);
+        assert_eq!(entries[1].job.status, TransferStatus::Paused);
+        assert_eq!(entries.last().unwrap().job.id, newest);
+    }
+}
@@ -2,6 +2,7 @@ import { useState, useEffect } from "react";
 import { SearchIcon } from "./ui/search";
 import { DatabaseIcon } from "lucide-react";
 import { S3Service, BucketInfo } from "../services/s3Service";
+import { CommandError } from "../services/commandError";
 import { useSettings } from "../services/settingsService";
 
 interface BucketListProps {
@@ -36,19 +37,19 @@ export function BucketList({
         const bucketList = await S3Service.listBuckets(activeConnection);
         setBuckets(bucketList);
       } catch (err) {
-        const errorMessage = err instanceof Error ? err.message : 'Failed to load buckets';
+        const failure = CommandError.from(err);
         
         // Provide more helpful error messages based on error type
-        if (errorMessage.includes('Access denied') || errorMessage.includes('AccessDenied')) {
+        if (failure.code === 'permission_denied') {
           setError('Access denied - Please check your S3 credentials and permissions in Settings');
-        } else if (errorMessage.includes('Invalid credentials') || errorMessage.includes('InvalidAccessKeyId')) {
+        } else if (failure.code === 'invalid_credentials') {
           setError('Invalid S3 credentials - Please verify your access key and secret key in Settings');
-        } else if (errorMessage.includes('Network error') || errorMessage.includes('timeout')) {
-          setError('Network connectivity issue - Please check your internet connection and endpoint URL');
-        } else if (errorMessage.includes('DNS resolution failed')) {
+        } else if (failure.message.includes('DNS resolution failed')) {
           setError('Cannot resolve S3 endpoint - Please verify the endpoint URL is correct in Settings');
+        } else if (failure.code === 'network_error') {
+          setError('Network connectivity issue - Please check your internet connection and endpoint URL');
         } else {
-          setError(`Failed to load buckets: ${errorMessage}`);
+          setError(`Failed to load buckets: ${failure.message}`);
         }
         
         console.error('Failed to load buckets:', err);
@@ -77,14 +78,14 @@ export function BucketList({
       const bucketList = await S3Service.listBuckets(activeConnection);
       setBuckets(bucketList);
     } catch (err) {
-      const errorMessage = err instanceof Error ? err.message : 'Failed to refresh buckets';
+      const failure = CommandError.from(err);
       
-      if (errorMessage.includes('Access denied') || errorMessage.includes('AccessDenied')) {
+      if (failure.code === 'permission_denied') {
         setError('Access denied - Please check your S3 credentials in Settings');
-      } else if (errorMessage.includes('Invalid credentials')) {
+      } else if (failure.code === 'invalid_credentials') {
         setError('Invalid S3 credentials - Please verify your access key and secret key in Settings');
       } else {
-        setError(`Failed to refresh buckets: ${errorMessage}`);
+        setError(`Failed to refresh buckets: ${failure.message}`);
       }
     } finally {
       setLoading(false);
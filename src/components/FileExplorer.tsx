@@ -5,7 +5,8 @@ import { GripIcon } from "./ui/grip";
 import { DownloadIcon } from "./ui/download";
 import { DeleteIcon } from "./ui/delete";
 import { LinkIcon } from "./ui/link";
-import { S3Service, ObjectInfo } from "../services/s3Service";
+import { listen } from "@tauri-apps/api/event";
+import { S3Service, ObjectInfo, ActiveView } from "../services/s3Service";
 import { useSettings } from "../services/settingsService";
 
 interface FileItem {
@@ -75,8 +76,7 @@ export function FileExplorer({
           activeConnection,
           bucketName,
           prefix,
-          "/", // Use delimiter to get folder-like structure
-          1000 // Max keys
+          "/" // Use delimiter to get folder-like structure; the page size comes from settings
         );
         
         console.log(`=== S3 RESPONSE ===`);
@@ -115,6 +115,22 @@ export function FileExplorer({
     loadObjects();
   }, [bucketName, currentPath, settings?.connections]);
 
+  // Point the backend's auto-refresh timer at the listing being shown
+  useEffect(() => {
+    if (!bucketName || !settings?.connections.length) return;
+
+    const activeConnection = settings.connections.find(conn => conn.isDefault) || settings.connections[0];
+    if (!activeConnection) return;
+
+    let prefix = currentPath === "/" ? "" : currentPath.substring(1);
+    if (prefix && !prefix.endsWith('/')) prefix += '/';
+
+    S3Service.setActiveView(activeConnection.name, bucketName, prefix).catch(() => {});
+    return () => {
+      S3Service.setActiveView(activeConnection.name).catch(() => {});
+    };
+  }, [bucketName, currentPath, settings?.connections]);
+
   // Convert S3 objects to FileItems
   const convertToFileItems = (): FileItem[] => {
     const items: FileItem[] = [];
@@ -259,7 +275,12 @@ export function FileExplorer({
         bucketName,
         prefix,
         "/",
-        1000
+        undefined,
+        undefined,
+        undefined,
+        undefined,
+        undefined,
+        true
       );
       
       console.log(`Refresh found ${response.objects.length} objects and ${response.common_prefixes.length} prefixes`);
@@ -273,6 +294,18 @@ export function FileExplorer({
     }
   };
 
+  const refreshRef = useRef(handleRefresh);
+  refreshRef.current = handleRefresh;
+
+  useEffect(() => {
+    const unlisten = listen<ActiveView>('refresh-tick', () => {
+      refreshRef.current();
+    });
+    return () => {
+      unlisten.then((stop) => stop());
+    };
+  }, []);
+
   // Handle navigation
   const handlePathClick = (path: string) => {
     console.log(`Breadcrumb navigation to path: "${path}"`);
@@ -439,7 +472,8 @@ export function FileExplorer({
       console.log(`Current path: "${currentPath}"`);
       console.log(`S3 Key for delete: "${fileKey}"`);
       
-      await S3Service.deleteObject(activeConnection, bucketName, fileKey);
+      // Only reached from the delete confirmation dialog
+      await S3Service.deleteObject(activeConnection, bucketName, fileKey, true);
       
       // Close modal
       setActiveModal(null);
@@ -2,8 +2,9 @@ import { useState, useEffect } from "react";
 import { useSettings, ConnectionConfig } from "../services/settingsService";
 import { FileDialogService } from "../services/fileDialogService";
 import { S3Service } from "../services/s3Service";
+import { CommandError } from "../services/commandError";
+import type { SizeUnits } from "../types/settings";
 import { S3Demo } from "./S3Demo";
-import { invoke } from '@tauri-apps/api/core';
 
 export function Settings() {
   const [activeTab, setActiveTab] = useState<
@@ -18,8 +19,10 @@ export function Settings() {
     error, 
     updateGeneralSettings,
     updateAppearanceSettings,
+    updateLayoutSettings,
     addConnection,
     updateConnection,
+    duplicateConnection,
     removeConnection,
     exportSettings: handleExportSettings, 
     importSettings: handleImportSettings,
@@ -48,6 +51,28 @@ export function Settings() {
   const [testResults, setTestResults] = useState<{[key: string]: boolean | string}>({});
   const [pingResults, setPingResults] = useState<{[key: string]: string}>({});
   const [pingingEndpoint, setPingingEndpoint] = useState<string | null>(null);
+  const [regionSuggestions, setRegionSuggestions] = useState<string[]>([]);
+  const [regionWarning, setRegionWarning] = useState<string | null>(null);
+
+  const serviceType = currentConnection?.serviceType;
+  const region = currentConnection?.region ?? "";
+
+  useEffect(() => {
+    if (!serviceType) return;
+    S3Service.listRegions(serviceType)
+      .then(setRegionSuggestions)
+      .catch(() => setRegionSuggestions([]));
+  }, [serviceType]);
+
+  useEffect(() => {
+    if (!serviceType || !region.trim()) {
+      setRegionWarning(null);
+      return;
+    }
+    S3Service.validateRegion(region, serviceType)
+      .then(setRegionWarning)
+      .catch(() => setRegionWarning(null));
+  }, [serviceType, region]);
 
 
 
@@ -73,17 +98,35 @@ export function Settings() {
     }
   };
 
-  const handlePingEndpoint = async (endpoint: string, connectionName: string) => {
+  // Pre-fills the endpoint from the provider preset unless the user already typed one
+  const handleServiceTypeChange = async (serviceType: string) => {
+    if (!currentConnection) return;
+    const updated = { ...currentConnection, serviceType };
+    setCurrentConnection(updated);
+    if (updated.endpoint.trim()) return;
+
+    try {
+      const endpoint = await S3Service.resolveProviderEndpoint(serviceType, updated.region);
+      setCurrentConnection(prev => (prev && !prev.endpoint.trim() ? { ...prev, endpoint } : prev));
+    } catch {
+      // No preset (custom provider) or it needs an account ID; leave the field for the user
+    }
+  };
+
+  const handlePingEndpoint = async (connection: ConnectionConfig, connectionName: string) => {
     setPingingEndpoint(connectionName);
     setPingResults(prev => ({ ...prev, [connectionName]: 'pinging...' }));
     
     try {
-      const result = await invoke<string>('ping_endpoint', { endpoint });
+      const probe = await S3Service.pingEndpoint(connection);
+      const result = probe.reachable && probe.looks_like_s3
+        ? `Endpoint reachable - ${probe.detail}, avg ${probe.latency_ms?.toFixed(0)} ms`
+        : probe.detail;
       setPingResults(prev => ({ ...prev, [connectionName]: result }));
     } catch (err) {
       setPingResults(prev => ({ 
         ...prev, 
-        [connectionName]: err instanceof Error ? err.message : 'Ping failed' 
+        [connectionName]: CommandError.from(err).message 
       }));
     } finally {
       setPingingEndpoint(null);
@@ -112,6 +155,19 @@ export function Settings() {
     }
   };
 
+  const handleDuplicateConnection = async (name: string) => {
+    if (!settings?.connections) return;
+
+    try {
+      const connectionIndex = settings.connections.findIndex((conn) => conn.name === name);
+      if (connectionIndex >= 0) {
+        await duplicateConnection(connectionIndex);
+      }
+    } catch (err) {
+      console.error('Failed to duplicate connection:', err);
+    }
+  };
+
   const handleDeleteConnection = async (name: string) => {
     if (!settings?.connections) return;
     
@@ -129,7 +185,15 @@ export function Settings() {
     try {
       const filePath = await FileDialogService.openJsonFile();
       if (filePath) {
-        await handleImportSettings(filePath);
+        try {
+          await handleImportSettings(filePath);
+        } catch (err) {
+          // Encrypted exports need the password they were created with
+          if (!(err instanceof CommandError && err.code === "password_required")) throw err;
+          const password = prompt("This settings file is password protected. Enter the password:");
+          if (!password) return;
+          await handleImportSettings(filePath, password);
+        }
         alert("Settings imported successfully");
       }
     } catch (err) {
@@ -144,7 +208,10 @@ export function Settings() {
     try {
       const filePath = await FileDialogService.saveJsonFile("settings-backup.json");
       if (filePath) {
-        await handleExportSettings(filePath);
+        // Exports include secret keys, so offer to encrypt them
+        const password = prompt("Password to encrypt the export (leave empty to export as plain text):");
+        if (password === null) return;
+        await handleExportSettings(filePath, password || undefined);
         alert("Settings exported successfully");
       }
     } catch (err) {
@@ -305,6 +372,147 @@ export function Settings() {
                 </div>
               </div>
 
+              <div className="form-control mb-4">
+                <label className="label cursor-pointer justify-start gap-2">
+                  <input
+                    type="checkbox"
+                    className="checkbox checkbox-primary"
+                    checked={settings.general.useTrash ?? false}
+                    onChange={(e) =>
+                      updateGeneralSettings({
+                        ...settings.general,
+                        useTrash: e.target.checked
+                      })
+                    }
+                  />
+                  <span className="label-text">
+                    Move deleted files to a trash folder in the bucket
+                  </span>
+                </label>
+              </div>
+
+              <div className="form-control mb-4">
+                <label className="label cursor-pointer justify-start gap-2">
+                  <input
+                    type="checkbox"
+                    className="checkbox checkbox-primary"
+                    checked={settings.general.autoConnectDefault ?? false}
+                    onChange={(e) =>
+                      updateGeneralSettings({
+                        ...settings.general,
+                        autoConnectDefault: e.target.checked
+                      })
+                    }
+                  />
+                  <span className="label-text">
+                    Connect to the default connection on startup
+                  </span>
+                </label>
+              </div>
+
+              <div className="form-control w-full max-w-xs mb-4">
+                <label className="label">
+                  <span className="label-text">Concurrent transfers</span>
+                </label>
+                <input
+                  type="number"
+                  min={1}
+                  max={32}
+                  className="input input-bordered w-full"
+                  value={settings.general.maxConcurrentTransfers ?? 4}
+                  onChange={(e) =>
+                    updateGeneralSettings({
+                      ...settings.general,
+                      maxConcurrentTransfers: parseInt(e.target.value) || 4
+                    })
+                  }
+                />
+              </div>
+
+              <div className="form-control w-full max-w-xs mb-4">
+                <label className="label">
+                  <span className="label-text">Bandwidth limit (KB/s)</span>
+                </label>
+                <input
+                  type="number"
+                  min={1}
+                  className="input input-bordered w-full"
+                  placeholder="Unlimited"
+                  value={
+                    settings.general.maxBytesPerSec
+                      ? Math.round(settings.general.maxBytesPerSec / 1024)
+                      : ""
+                  }
+                  onChange={(e) =>
+                    updateGeneralSettings({
+                      ...settings.general,
+                      maxBytesPerSec: parseInt(e.target.value) * 1024 || undefined
+                    })
+                  }
+                />
+              </div>
+
+              <div className="form-control w-full max-w-xs mb-4">
+                <label className="label">
+                  <span className="label-text">Size units</span>
+                </label>
+                <select
+                  className="select select-bordered w-full"
+                  value={settings.general.sizeUnits ?? "binary"}
+                  onChange={(e) =>
+                    updateGeneralSettings({
+                      ...settings.general,
+                      sizeUnits: e.target.value as SizeUnits
+                    })
+                  }
+                >
+                  <option value="binary">Binary (KiB, MiB)</option>
+                  <option value="decimal">Decimal (KB, MB)</option>
+                </select>
+              </div>
+
+              <div className="form-control w-full max-w-xs mb-4">
+                <label className="label">
+                  <span className="label-text">Objects per page</span>
+                </label>
+                <input
+                  type="number"
+                  className="input input-bordered w-full"
+                  min="1"
+                  max="1000"
+                  value={settings.layout.defaultPageSize ?? 1000}
+                  onChange={(e) =>
+                    updateLayoutSettings({
+                      ...settings.layout,
+                      defaultPageSize: Math.min(Math.max(parseInt(e.target.value) || 1, 1), 1000)
+                    })
+                  }
+                />
+              </div>
+
+              <div className="form-control w-full max-w-xs mb-4">
+                <label className="label">
+                  <span className="label-text">Log level</span>
+                </label>
+                <select
+                  className="select select-bordered w-full"
+                  value={settings.general.logLevel ?? "info"}
+                  onChange={(e) =>
+                    updateGeneralSettings({
+                      ...settings.general,
+                      logLevel: e.target.value
+                    })
+                  }
+                >
+                  <option value="off">Off</option>
+                  <option value="error">Error</option>
+                  <option value="warn">Warning</option>
+                  <option value="info">Info</option>
+                  <option value="debug">Debug</option>
+                  <option value="trace">Trace</option>
+                </select>
+              </div>
+
               <div className="mt-8 flex gap-2">
                 <button className="btn btn-outline" onClick={refreshSettings}>
                   <span>Refresh from File</span>
@@ -413,7 +621,7 @@ export function Settings() {
                               <div className="flex gap-1">
                                 <button
                                   className="btn btn-xs btn-ghost"
-                                  onClick={() => handlePingEndpoint(conn.endpoint, conn.name)}
+                                  onClick={() => handlePingEndpoint(conn, conn.name)}
                                   disabled={pingingEndpoint === conn.name}
                                   title="Test basic connectivity"
                                 >
@@ -433,6 +641,12 @@ export function Settings() {
                                 >
                                   Edit
                                 </button>
+                                <button
+                                  className="btn btn-xs btn-ghost"
+                                  onClick={() => handleDuplicateConnection(conn.name)}
+                                >
+                                  Duplicate
+                                </button>
                                 <button
                                   className="btn btn-xs btn-ghost btn-error"
                                   onClick={() =>
@@ -491,16 +705,14 @@ export function Settings() {
                       <select
                         className="select select-bordered w-full"
                         value={currentConnection.serviceType}
-                        onChange={(e) =>
-                          setCurrentConnection({
-                            ...currentConnection,
-                            serviceType: e.target.value,
-                          })
-                        }
+                        onChange={(e) => handleServiceTypeChange(e.target.value)}
                       >
                         <option>Amazon S3</option>
                         <option>MinIO</option>
+                        <option>Wasabi</option>
+                        <option>Backblaze B2</option>
                         <option>DigitalOcean Spaces</option>
+                        <option>Cloudflare R2</option>
                         <option>Google Cloud Storage</option>
                         <option>Custom S3 Compatible</option>
                       </select>
@@ -527,6 +739,58 @@ export function Settings() {
                       </div>
                     </div>
 
+                    <div className="form-control mb-4">
+                      <label className="label cursor-pointer justify-start gap-2">
+                        <input
+                          type="checkbox"
+                          className="checkbox checkbox-primary"
+                          checked={currentConnection.anonymous ?? false}
+                          onChange={(e) =>
+                            setCurrentConnection({
+                              ...currentConnection,
+                              anonymous: e.target.checked,
+                            })
+                          }
+                        />
+                        <span className="label-text">
+                          Anonymous access (public buckets, no keys)
+                        </span>
+                      </label>
+                    </div>
+
+                    <div className="form-control mb-4">
+                      <label className="label cursor-pointer justify-start gap-2">
+                        <input
+                          type="checkbox"
+                          className="checkbox checkbox-primary"
+                          checked={currentConnection.useDefaultCredentialChain ?? false}
+                          onChange={(e) =>
+                            setCurrentConnection({
+                              ...currentConnection,
+                              useDefaultCredentialChain: e.target.checked,
+                            })
+                          }
+                        />
+                        <span className="label-text">
+                          Use AWS credentials from the environment or ~/.aws
+                        </span>
+                      </label>
+                      {currentConnection.useDefaultCredentialChain && (
+                        <input
+                          type="text"
+                          className="input input-bordered w-full mt-2"
+                          placeholder="Profile name (optional)"
+                          value={currentConnection.profileName ?? ""}
+                          onChange={(e) =>
+                            setCurrentConnection({
+                              ...currentConnection,
+                              profileName: e.target.value,
+                            })
+                          }
+                        />
+                      )}
+                    </div>
+
                     <div className="form-control w-full">
                       <label className="label">
                         <span className="label-text">Access Key</span>
@@ -535,6 +799,7 @@ export function Settings() {
                         type="text"
                         className="input input-bordered w-full"
                         value={currentConnection.accessKey}
+                        disabled={currentConnection.anonymous || currentConnection.useDefaultCredentialChain}
                         onChange={(e) =>
                           setCurrentConnection({
                             ...currentConnection,
@@ -552,6 +817,7 @@ export function Settings() {
                         type="password"
                         className="input input-bordered w-full"
                         value={currentConnection.secretKey}
+                        disabled={currentConnection.anonymous || currentConnection.useDefaultCredentialChain}
                         onChange={(e) =>
                           setCurrentConnection({
                             ...currentConnection,
@@ -569,6 +835,7 @@ export function Settings() {
                         type="text"
                         className="input input-bordered w-full"
                         placeholder="us-east-1"
+                        list="region-suggestions"
                         value={currentConnection.region}
                         onChange={(e) =>
                           setCurrentConnection({
@@ -577,6 +844,14 @@ export function Settings() {
                           })
                         }
                       />
+                      <datalist id="region-suggestions">
+                        {regionSuggestions.map((suggestion) => (
+                          <option key={suggestion} value={suggestion} />
+                        ))}
+                      </datalist>
+                      {regionWarning && (
+                        <div className="text-xs text-warning mt-1">{regionWarning}</div>
+                      )}
                       <div className="text-xs text-gray-500 mt-1">
                         Leave empty for default region. Examples: us-east-1, eu-west-1, ap-southeast-1
                       </div>
@@ -601,10 +876,114 @@ export function Settings() {
                       </label>
                     </div>
 
+                    <div className="form-control mb-4">
+                      <label className="label cursor-pointer justify-start gap-2">
+                        <input
+                          type="checkbox"
+                          className="checkbox checkbox-primary"
+                          checked={currentConnection.readOnly ?? false}
+                          onChange={(e) =>
+                            setCurrentConnection({
+                              ...currentConnection,
+                              readOnly: e.target.checked,
+                            })
+                          }
+                        />
+                        <span className="label-text">
+                          Read-only (block uploads, copies and deletes)
+                        </span>
+                      </label>
+                    </div>
+
+                    <div className="form-control mb-4">
+                      <label className="label cursor-pointer justify-start gap-2">
+                        <input
+                          type="checkbox"
+                          className="checkbox checkbox-primary"
+                          checked={currentConnection.requestPayer ?? false}
+                          onChange={(e) =>
+                            setCurrentConnection({
+                              ...currentConnection,
+                              requestPayer: e.target.checked,
+                            })
+                          }
+                        />
+                        <span className="label-text">
+                          Requester pays (your account is billed for requests)
+                        </span>
+                      </label>
+                    </div>
+
+                    <div className="form-control mb-4">
+                      <label className="label">
+                        <span className="label-text">Max connections per host</span>
+                      </label>
+                      <input
+                        type="number"
+                        min={1}
+                        className="input input-bordered w-full"
+                        placeholder="Unlimited"
+                        value={currentConnection.maxConnections ?? ""}
+                        onChange={(e) =>
+                          setCurrentConnection({
+                            ...currentConnection,
+                            maxConnections: parseInt(e.target.value) || undefined,
+                          })
+                        }
+                      />
+                      <div className="text-xs text-gray-500 mt-1">
+                        More connections let batch operations run in parallel. Fewer ease the load on
+                        servers that rate-limit, but requests wait for a free connection.
+                      </div>
+                    </div>
+
+                    <details className="mb-4">
+                      <summary className="cursor-pointer text-sm font-medium">Advanced (AWS only)</summary>
+                      <div className="form-control">
+                        <label className="label cursor-pointer justify-start gap-2">
+                          <input
+                            type="checkbox"
+                            className="checkbox checkbox-primary"
+                            checked={currentConnection.useAccelerate ?? false}
+                            onChange={(e) =>
+                              setCurrentConnection({
+                                ...currentConnection,
+                                useAccelerate: e.target.checked,
+                              })
+                            }
+                          />
+                          <span className="label-text">
+                            Use S3 Transfer Acceleration
+                          </span>
+                        </label>
+                      </div>
+                      <div className="form-control">
+                        <label className="label cursor-pointer justify-start gap-2">
+                          <input
+                            type="checkbox"
+                            className="checkbox checkbox-primary"
+                            checked={currentConnection.useDualstack ?? false}
+                            onChange={(e) =>
+                              setCurrentConnection({
+                                ...currentConnection,
+                                useDualstack: e.target.checked,
+                              })
+                            }
+                          />
+                          <span className="label-text">
+                            Use dualstack (IPv4/IPv6) endpoints
+                          </span>
+                        </label>
+                      </div>
+                      <div className="text-xs text-gray-500 mt-1">
+                        Ignored for custom endpoints such as MinIO. Acceleration must be enabled on the bucket.
+                      </div>
+                    </details>
+
                     <div className="pt-4 space-y-2">
                       <button
                         className="btn btn-outline btn-sm w-full"
-                        onClick={() => currentConnection && handlePingEndpoint(currentConnection.endpoint, currentConnection.name || 'temp')}
+                        onClick={() => currentConnection && handlePingEndpoint(currentConnection, currentConnection.name || 'temp')}
                         disabled={!currentConnection?.endpoint || pingingEndpoint === (currentConnection?.name || 'temp')}
                       >
                         {pingingEndpoint === (currentConnection?.name || 'temp') ? (
@@ -626,7 +1005,7 @@ export function Settings() {
                       <button
                         className="btn btn-outline w-full"
                         onClick={() => currentConnection && handleTestConnection(currentConnection)}
-                        disabled={!currentConnection?.endpoint || !currentConnection?.accessKey || testingConnection === currentConnection?.name}
+                        disabled={!currentConnection?.endpoint || (!currentConnection?.anonymous && !currentConnection?.useDefaultCredentialChain && !currentConnection?.accessKey) || testingConnection === currentConnection?.name}
                       >
                         {testingConnection === currentConnection?.name ? (
                           <>
@@ -647,7 +1026,7 @@ export function Settings() {
                       <button
                         className="btn btn-primary w-full"
                         onClick={handleSaveConnection}
-                        disabled={!currentConnection?.name || !currentConnection?.endpoint || !currentConnection?.accessKey || !currentConnection?.secretKey}
+                        disabled={!currentConnection?.name || !currentConnection?.endpoint || (!currentConnection?.anonymous && !currentConnection?.useDefaultCredentialChain && (!currentConnection?.accessKey || !currentConnection?.secretKey))}
                       >
                         Save Connection
                       </button>
@@ -0,0 +1,50 @@
+// Mirrors ErrorCode in src-tauri/src/command_error.rs
+export type ErrorCode =
+  | 'invalid_credentials'
+  | 'bucket_not_found'
+  | 'object_not_found'
+  | 'permission_denied'
+  | 'connection_not_found'
+  | 'network_error'
+  | 'configuration_error'
+  | 'io_error'
+  | 'destination_exists'
+  | 'cancelled'
+  | 'move_incomplete'
+  | 'checksum_mismatch'
+  | 'object_changed'
+  | 'precondition_failed'
+  | 'clock_skew'
+  | 'password_required'
+  | 'unknown';
+
+// Shape of the error every Tauri command rejects with
+export interface RustCommandError {
+  code: ErrorCode;
+  message: string;
+  retryable: boolean;
+}
+
+export class CommandError extends Error {
+  readonly code: ErrorCode;
+  readonly retryable: boolean;
+
+  constructor(error: RustCommandError) {
+    super(error.message);
+    this.name = 'CommandError';
+    this.code = error.code;
+    this.retryable = error.retryable;
+  }
+
+  // Wraps whatever `invoke` rejected with; plain strings come from Tauri itself
+  static from(error: unknown): CommandError {
+    if (error instanceof CommandError) {
+      return error;
+    }
+    if (error && typeof error === 'object' && 'code' in error && 'message' in error) {
+      return new CommandError(error as RustCommandError);
+    }
+    const message = error instanceof Error ? error.message : String(error);
+    return new CommandError({ code: 'unknown', message, retryable: false });
+  }
+}
@@ -1,5 +1,6 @@
 import { invoke } from '@tauri-apps/api/core';
 import { ConnectionConfig, convertToRust } from './settingsService';
+import { CommandError, type ErrorCode, type RustCommandError } from './commandError';
 
 export interface BucketInfo {
   name: string;
@@ -7,6 +8,11 @@ export interface BucketInfo {
   region?: string;
 }
 
+export interface FormattedObjectInfo {
+  size_human: string | null;
+  modified_relative: string | null;
+}
+
 export interface ObjectInfo {
   key: string;
   size?: number;
@@ -15,22 +21,471 @@ export interface ObjectInfo {
   storage_class?: string;
   content_type?: string;
   is_folder: boolean;
+  restore_status?: string;
+  // Object lock state, only filled in by getObjectInfo
+  lock_mode?: RetentionMode;
+  lock_retain_until?: string;
+  legal_hold?: boolean;
+  // Stored checksum, only filled in by getObjectInfo
+  checksum?: ObjectChecksum;
+}
+
+export type ChecksumAlgorithm = 'CRC32C' | 'SHA256';
+
+// value is base64; checksums of multipart uploads end in "-<parts>"
+export interface ObjectChecksum {
+  algorithm: ChecksumAlgorithm;
+  value: string;
+}
+
+export type RetentionMode = 'GOVERNANCE' | 'COMPLIANCE';
+
+export interface ObjectRetention {
+  mode?: RetentionMode;
+  retain_until?: string;
 }
 
+export type SortField = 'name' | 'size' | 'modified';
+export type SortDirection = 'asc' | 'desc';
+
 export interface ListObjectsResponse {
   objects: ObjectInfo[];
   common_prefixes: string[];
   is_truncated: boolean;
   next_continuation_token?: string;
   prefix?: string;
+  // Objects plus common prefixes as S3 counted them, before pattern filtering
+  key_count: number;
+}
+
+export interface PrefixStats {
+  object_count: number;
+  total_bytes: number;
+  largest_object_key?: string;
+  largest_object_size: number;
+}
+
+export interface ObjectTag {
+  key: string;
+  value: string;
+}
+
+export type VersioningState = 'Unversioned' | 'Enabled' | 'Suspended';
+
+export interface BucketVersioningStatus {
+  status: VersioningState;
+  mfa_delete: boolean;
+}
+
+export interface ObjectVersion {
+  key: string;
+  version_id: string;
+  size?: number;
+  last_modified?: string;
+  is_latest: boolean;
+}
+
+export interface DeleteMarker {
+  key: string;
+  version_id: string;
+  last_modified?: string;
+  is_latest: boolean;
+}
+
+export interface ListObjectVersionsResponse {
+  versions: ObjectVersion[];
+  delete_markers: DeleteMarker[];
+  is_truncated: boolean;
+  next_key_marker?: string;
+  next_version_id_marker?: string;
+}
+
+// An upload that was started but never completed or aborted
+export interface MultipartUploadInfo {
+  key: string;
+  upload_id: string;
+  initiated?: string;
+}
+
+export interface KeyFailure {
+  key: string;
+  error: string;
+}
+
+// error_code is the S3 code, e.g. "AccessDenied" for objects under object lock
+export interface DeleteResult {
+  key: string;
+  success: boolean;
+  error_code?: string;
+  error_message?: string;
+}
+
+// 'copied' keys reached the destination but their originals remain
+export interface KeyMove {
+  source_key: string;
+  dest_key: string;
+  status: 'moved' | 'copied' | 'failed';
+  error?: string;
+}
+
+export interface BatchResult {
+  succeeded: string[];
+  failed: KeyFailure[];
+  // When true nothing was changed; `succeeded` lists the keys that would have been
+  dry_run: boolean;
+}
+
+// Outcome of a folder download by key; skipped files were already on disk
+export interface DirectoryDownloadResult {
+  downloaded: string[];
+  skipped: string[];
+  failed: KeyFailure[];
+}
+
+// Also the payload of the `empty-bucket-progress` event, with the totals so far
+export interface EmptyBucketResult {
+  deleted: number;
+  failed: KeyFailure[];
+  bucket_deleted: boolean;
+}
+
+export interface ListAllObjectsResponse {
+  objects: ObjectInfo[];
+  is_truncated: boolean;
+}
+
+export type SseAlgorithm = 'AES256' | 'aws:kms';
+
+// server_side_encryption and kms_key_id echo the encryption S3 applied
+export interface UploadResult {
+  etag: string;
+  server_side_encryption?: string;
+  kms_key_id?: string;
+  checksum?: ObjectChecksum;
+}
+
+// Default encryption for new objects; no kms_key_id with aws:kms means the AWS managed key
+export interface EncryptionConfig {
+  sse_algorithm: SseAlgorithm;
+  kms_key_id?: string;
+  bucket_key_enabled: boolean;
+}
+
+// grantee_type is CanonicalUser, AmazonCustomerByEmail or Group; groups are identified by uri
+export interface AclGrant {
+  grantee_type: string;
+  id: string | null;
+  display_name: string | null;
+  email: string | null;
+  uri: string | null;
+  permission: string | null;
+}
+
+export interface ObjectAcl {
+  owner: { id: string | null; display_name: string | null } | null;
+  grants: AclGrant[];
+}
+
+// Either index_document (with an optional error_document) or redirect_all_to is set;
+// redirect_all_to is a host name, optionally prefixed with http:// or https://
+export interface WebsiteConfig {
+  index_document: string | null;
+  error_document: string | null;
+  redirect_all_to: string | null;
+}
+
+// Server access logs for the bucket are written to target_bucket under target_prefix
+export interface LoggingConfig {
+  enabled: boolean;
+  target_bucket: string | null;
+  target_prefix: string | null;
+}
+
+// arn is an SNS topic, SQS queue or Lambda function; MinIO webhook and AMQP targets
+// are queue ARNs like 'arn:minio:sqs::primary:webhook'. Events look like 's3:ObjectCreated:*'.
+export interface NotificationDestination {
+  id: string | null;
+  arn: string;
+  events: string[];
+  prefix: string | null;
+  suffix: string | null;
+}
+
+export interface NotificationConfig {
+  topics: NotificationDestination[];
+  queues: NotificationDestination[];
+  lambda_functions: NotificationDestination[];
+  event_bridge: boolean;
+}
+
+// region is where the bucket lives, when S3 said so
+export interface BucketAccess {
+  exists: boolean;
+  accessible: boolean;
+  region?: string;
+}
+
+// Best-effort feature probes; false also covers "couldn't tell"
+export interface BucketCapabilities {
+  versioning: boolean;
+  versioning_enabled: boolean;
+  object_lock: boolean;
+  tagging: boolean;
+  select: boolean;
 }
 
+// Round-trip times of the successful samples; success_rate is between 0 and 1
+export interface LatencyStats {
+  min_ms: number;
+  max_ms: number;
+  avg_ms: number;
+  success_rate: number;
+}
+
+// tls_ok is null for plain http or when no connection was made
+export interface EndpointProbe {
+  reachable: boolean;
+  looks_like_s3: boolean;
+  tls_ok: boolean | null;
+  latency_ms: number | null;
+  detail: string;
+}
+
+// size and content_type are only set when the object was checked before signing
 export interface PresignedUrlResponse {
   url: string;
   expires_in: number;
+  size: number | null;
+  content_type: string | null;
+}
+
+export interface PresignedPostResponse {
+  url: string;
+  fields: Record<string, string>;
+  expires_in: number;
+}
+
+export interface TransferProgress {
+  id: string;
+  key: string;
+  bytes_transferred: number;
+  total_bytes: number;
+  rate_bytes_per_sec: number;
+}
+
+export interface TransferFailure {
+  id: string;
+  key: string;
+  error: string;
+}
+
+export type MetadataDirective = 'COPY' | 'REPLACE';
+
+// REPLACE needs a content type or metadata; both are rejected with COPY
+export interface CopyOptions {
+  metadata_directive?: MetadataDirective;
+  storage_class?: string;
+  content_type?: string;
+  metadata?: Record<string, string>;
+  acl?: string;
+  // Conditions on the destination; a mismatch fails with 'precondition_failed'
+  if_match?: string;
+  if_none_match?: string;
+}
+
+export type TransferKind = 'upload' | 'download';
+
+export type TransferStatus = 'queued' | 'running' | 'paused' | 'completed' | 'failed' | 'cancelled';
+
+// A queued transfer; also the payload of the `transfer-status` event
+export interface TransferJob {
+  id: string;
+  kind: TransferKind;
+  connection_name: string;
+  bucket: string;
+  key: string;
+  local_path: string;
+  status: TransferStatus;
+  bytes_done: number;
+  total: number;
+  error?: string;
+  error_code?: ErrorCode;
+  upload_id?: string;
+  overwrite: boolean;
+}
+
+export type RestoreTier = 'Expedited' | 'Standard' | 'Bulk';
+
+export interface ObjectRangeResponse {
+  data: string; // base64
+  content_range?: string;
+  total_size?: number;
+  range_ignored: boolean;
+}
+
+export interface ImageThumbnail {
+  data: string; // base64
+  content_type: 'image/png' | 'image/jpeg';
+  width: number;
+  height: number;
+}
+
+export interface TextObjectResponse {
+  content: string; // empty when is_binary
+  truncated: boolean;
+  encoding: 'utf-8' | 'utf-8-lossy' | '';
+  is_binary: boolean;
+}
+
+export interface SearchObjectsResponse {
+  matches: ObjectInfo[];
+  scanned: number;
+  is_truncated: boolean;
+}
+
+// Payload of the `search-progress` event: the matches found in one listing page
+export interface SearchProgress {
+  id: string;
+  matches: ObjectInfo[];
+  scanned: number;
+}
+
+// How S3 Select parses the object; delimiters, quote and header only apply to CSV
+export interface SelectInput {
+  format?: 'csv' | 'json' | 'parquet';
+  compression?: 'none' | 'gzip' | 'bzip2';
+  field_delimiter?: string;
+  record_delimiter?: string;
+  quote_character?: string;
+  has_header?: boolean;
+  json_document?: boolean;
+}
+
+export type SelectOutputFormat = 'csv' | 'json';
+
+export interface SelectResult {
+  records: string; // empty when streamed
+  truncated: boolean;
+  bytes_scanned?: number;
+  bytes_processed?: number;
+  bytes_returned?: number;
+}
+
+// Payload of the `select-records` event: whole records of a streaming query
+export interface SelectRecords {
+  id: string;
+  records: string;
+}
+
+// Payload of the `connected` and `connect-failed` events sent after startup auto-connect
+export interface ConnectionEvent {
+  connection_name: string;
+  error: RustCommandError | null;
+}
+
+// Payload of the `connection-lost` and `connection-restored` events. attempt is the
+// automatic reconnect that succeeded, null for a manual reconnect.
+export interface ConnectionHealthEvent {
+  connection_name: string;
+  attempt: number | null;
+}
+
+// Payload of the `objects-added`, `objects-removed` and `objects-changed` events.
+// truncated means the prefix is too large to watch every object in it.
+export interface PrefixChanges {
+  watch_id: string;
+  bucket: string;
+  prefix: string;
+  objects: ObjectInfo[];
+  truncated: boolean;
+}
+
+// Payload of the `refresh-tick` event emitted while auto-refresh is on
+export interface ActiveView {
+  connection_name: string;
+  bucket: string;
+  prefix: string;
+}
+
+// The root breadcrumb has an empty name and prefix
+export interface Breadcrumb {
+  name: string;
+  prefix: string;
+}
+
+// endpoint_template contains "{region}" and/or "{account_id}" placeholders
+export interface ProviderPreset {
+  name: string;
+  endpoint_template: string;
+  default_region: string;
+  force_path_style: boolean;
+  // Empty where any region name is accepted
+  regions: string[];
 }
 
 export class S3Service {
+  static async listProviderPresets(): Promise<ProviderPreset[]> {
+    try {
+      return await invoke<ProviderPreset[]>('list_provider_presets');
+    } catch (error) {
+      console.error('Failed to list provider presets:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Root first, then one entry per level; a trailing slash on prefix is optional
+  static async getPrefixBreadcrumbs(prefix: string): Promise<Breadcrumb[]> {
+    try {
+      return await invoke<Breadcrumb[]>('get_s3_prefix_breadcrumbs', { prefix });
+    } catch (error) {
+      console.error('Failed to get prefix breadcrumbs:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // e.g. "a/b/" for "a/b/c/"; the root ("") is its own parent
+  static async getParentPrefix(prefix: string): Promise<string> {
+    try {
+      return await invoke<string>('get_s3_parent_prefix', { prefix });
+    } catch (error) {
+      console.error('Failed to get parent prefix:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Known regions of the provider; AWS's when serviceType is omitted
+  static async listRegions(serviceType?: string): Promise<string[]> {
+    try {
+      return await invoke<string[]>('list_s3_regions', { serviceType: serviceType ?? null });
+    } catch (error) {
+      console.error('Failed to list regions:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Resolves to a warning for an unknown region, or null; never rejects an unknown one
+  static async validateRegion(region: string, serviceType?: string): Promise<string | null> {
+    try {
+      return await invoke<string | null>('validate_s3_region', { serviceType: serviceType ?? null, region });
+    } catch (error) {
+      console.error('Failed to validate region:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async resolveProviderEndpoint(serviceType: string, region: string, accountId?: string): Promise<string> {
+    try {
+      return await invoke<string>('resolve_provider_endpoint', {
+        serviceType,
+        region,
+        accountId: accountId || null,
+      });
+    } catch (error) {
+      console.error('Failed to resolve provider endpoint:', error);
+      throw CommandError.from(error);
+    }
+  }
+
   static async testConnection(connection: ConnectionConfig): Promise<boolean> {
     try {
       const result = await invoke<boolean>('test_s3_connection', {
@@ -39,19 +494,76 @@ export class S3Service {
       return result;
     } catch (error) {
       console.error('Failed to test S3 connection:', error);
-      throw new Error(error as string);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async disconnect(connectionName: string): Promise<void> {
+    try {
+      await invoke('disconnect_from_s3', { connectionName });
+    } catch (error) {
+      console.error('Failed to disconnect from S3:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Retries a lost connection now instead of waiting for the automatic reconnects
+  static async reconnect(connectionName: string): Promise<void> {
+    try {
+      await invoke('reconnect_s3', { connectionName });
+    } catch (error) {
+      console.error('Failed to reconnect to S3:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Drops every live connection, e.g. on logout
+  static async disconnectAll(): Promise<void> {
+    try {
+      await invoke('disconnect_all_s3');
+    } catch (error) {
+      console.error('Failed to disconnect all S3 connections:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async listBuckets(connection: ConnectionConfig): Promise<BucketInfo[]> {
+  static async measureLatency(connection: ConnectionConfig, samples = 5): Promise<LatencyStats> {
+    try {
+      return await invoke<LatencyStats>('measure_s3_latency', {
+        connectionConfig: convertToRust.connection(connection),
+        samples,
+      });
+    } catch (error) {
+      console.error('Failed to measure S3 latency:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Resolves even when the endpoint can't be reached; see reachable and detail
+  static async pingEndpoint(connection: ConnectionConfig, count = 3): Promise<EndpointProbe> {
+    try {
+      return await invoke<EndpointProbe>('ping_endpoint', {
+        endpoint: connection.endpoint,
+        allowInvalidCerts: connection.allowInvalidCerts ?? false,
+        proxyUrl: connection.proxyUrl || null,
+        count,
+      });
+    } catch (error) {
+      console.error('Failed to ping endpoint:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async listBuckets(connection: ConnectionConfig, includeRegions = false): Promise<BucketInfo[]> {
     try {
       const buckets = await invoke<BucketInfo[]>('list_s3_buckets_with_config', {
         connectionConfig: convertToRust.connection(connection),
+        includeRegions,
       });
       return buckets;
     } catch (error) {
       console.error('Failed to list S3 buckets:', error);
-      throw new Error(error as string);
+      throw CommandError.from(error);
     }
   }
 
@@ -61,7 +573,14 @@ export class S3Service {
     prefix?: string,
     delimiter?: string,
     maxKeys?: number,
-    continuationToken?: string
+    continuationToken?: string,
+    sortBy?: SortField,
+    sortDirection?: SortDirection,
+    pattern?: string,
+    // Skips the backend's listing cache
+    refresh?: boolean,
+    // Fills in content_type with a HEAD request per object; slow for large pages
+    enrich?: boolean
   ): Promise<ListObjectsResponse> {
     try {
       // Normalize prefix for S3: 
@@ -83,182 +602,1298 @@ export class S3Service {
       console.log(`S3Service.listObjects - Bucket: ${bucket}, Normalized prefix: "${normalizedPrefix || ''}"`);
       
       const response = await invoke<ListObjectsResponse>('list_s3_objects', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
         bucket,
         prefix: normalizedPrefix || null,
         delimiter: delimiter || null,
         maxKeys: maxKeys || null,
         continuationToken: continuationToken || null,
+        sortBy: sortBy || null,
+        sortDirection: sortDirection || null,
+        pattern: pattern || null,
+        refresh: refresh ?? null,
+        enrich: enrich ?? null,
       });
       return response;
     } catch (error) {
       console.error('Failed to list S3 objects:', error);
-      throw new Error(error as string);
+      throw CommandError.from(error);
     }
   }
 
-  static async getObjectInfo(
-    connection: ConnectionConfig,
-    bucket: string,
-    key: string
-  ): Promise<ObjectInfo> {
+  // Drops cached listings under or above prefix, or the whole bucket without one
+  static async invalidateCache(connection: ConnectionConfig, bucket: string, prefix?: string): Promise<void> {
     try {
-      const info = await invoke<ObjectInfo>('get_s3_object_info', {
-        connectionConfig: convertToRust.connection(connection),
+      await invoke('invalidate_s3_cache', {
+        connectionName: connection.name,
         bucket,
-        key,
+        prefix: prefix ?? null,
       });
-      return info;
     } catch (error) {
-      console.error('Failed to get S3 object info:', error);
-      throw new Error(error as string);
+      console.error('Failed to invalidate S3 cache:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async deleteObject(
+  static async clearCache(): Promise<void> {
+    try {
+      await invoke('clear_s3_cache');
+    } catch (error) {
+      console.error('Failed to clear S3 cache:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async listAllObjects(
     connection: ConnectionConfig,
     bucket: string,
-    key: string
-  ): Promise<void> {
+    prefix?: string,
+    maxTotal?: number,
+    sortBy?: SortField,
+    sortDirection?: SortDirection
+  ): Promise<ListAllObjectsResponse> {
     try {
-      await invoke('delete_s3_object', {
+      return await invoke<ListAllObjectsResponse>('list_all_s3_objects', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
         bucket,
-        key,
+        prefix: prefix || null,
+        maxTotal: maxTotal || null,
+        sortBy: sortBy || null,
+        sortDirection: sortDirection || null,
       });
     } catch (error) {
-      console.error('Failed to delete S3 object:', error);
-      throw new Error(error as string);
+      console.error('Failed to list all S3 objects:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async deleteObjects(
+  static async getPrefixStats(
     connection: ConnectionConfig,
     bucket: string,
-    keys: string[]
-  ): Promise<string[]> {
+    prefix?: string
+  ): Promise<PrefixStats> {
     try {
-      const failedKeys = await invoke<string[]>('delete_s3_objects', {
+      return await invoke<PrefixStats>('get_s3_prefix_stats', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
         bucket,
-        keys,
+        prefix: prefix || null,
       });
-      return failedKeys;
     } catch (error) {
-      console.error('Failed to delete S3 objects:', error);
-      throw new Error(error as string);
+      console.error('Failed to get S3 prefix stats:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async createBucket(
+  // Returns the watch id; changes arrive as objects-added/removed/changed events
+  static async watchPrefix(
     connection: ConnectionConfig,
     bucket: string,
-    region?: string
-  ): Promise<void> {
+    prefix: string,
+    intervalSecs: number
+  ): Promise<string> {
     try {
-      await invoke('create_s3_bucket', {
+      return await invoke<string>('watch_s3_prefix', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
         bucket,
-        region: region || null,
+        prefix: prefix || null,
+        intervalSecs,
       });
     } catch (error) {
-      console.error('Failed to create S3 bucket:', error);
-      throw new Error(error as string);
+      console.error('Failed to watch S3 prefix:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async deleteBucket(
-    connection: ConnectionConfig,
-    bucket: string
-  ): Promise<void> {
+  static async unwatchPrefix(watchId: string): Promise<boolean> {
     try {
-      await invoke('delete_s3_bucket', {
-        connectionConfig: convertToRust.connection(connection),
-        bucket,
-      });
+      return await invoke<boolean>('unwatch_s3_prefix', { watchId });
     } catch (error) {
-      console.error('Failed to delete S3 bucket:', error);
-      throw new Error(error as string);
+      console.error('Failed to stop watching S3 prefix:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async createFolder(
+  static async searchObjects(
     connection: ConnectionConfig,
+    searchId: string,
     bucket: string,
-    folderPath: string
-  ): Promise<void> {
+    query: string,
+    prefix?: string,
+    caseSensitive = false,
+    maxResults?: number
+  ): Promise<SearchObjectsResponse> {
     try {
-      await invoke('create_s3_folder', {
+      return await invoke<SearchObjectsResponse>('search_s3_objects', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
+        searchId,
         bucket,
-        folderPath,
+        prefix: prefix || null,
+        query,
+        caseSensitive,
+        maxResults: maxResults || null,
       });
     } catch (error) {
-      console.error('Failed to create S3 folder:', error);
-      throw new Error(error as string);
+      console.error('Failed to search S3 objects:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async generateDownloadUrl(
+  // Runs SQL over a CSV, JSON or Parquet object. Passing a queryId streams the
+  // records as `select-records` events instead of returning them.
+  static async selectQuery(
     connection: ConnectionConfig,
     bucket: string,
     key: string,
-    expiresInSecs: number = 3600
-  ): Promise<PresignedUrlResponse> {
+    expression: string,
+    inputFormat?: SelectInput,
+    outputFormat?: SelectOutputFormat,
+    queryId?: string
+  ): Promise<SelectResult> {
     try {
-      const response = await invoke<PresignedUrlResponse>('generate_s3_download_url', {
+      return await invoke<SelectResult>('s3_select_query', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
         bucket,
         key,
-        expiresInSecs,
+        expression,
+        inputFormat: inputFormat ?? null,
+        outputFormat: outputFormat ?? null,
+        queryId: queryId ?? null,
       });
-      return response;
     } catch (error) {
-      console.error('Failed to generate S3 download URL:', error);
-      throw new Error(error as string);
+      console.error('Failed to run S3 Select query:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async generateUploadUrl(
+  static async getObjectInfo(
     connection: ConnectionConfig,
     bucket: string,
-    key: string,
-    expiresInSecs: number = 3600,
-    contentType?: string
-  ): Promise<PresignedUrlResponse> {
+    key: string
+  ): Promise<ObjectInfo> {
     try {
-      const response = await invoke<PresignedUrlResponse>('generate_s3_upload_url', {
+      const info = await invoke<ObjectInfo>('get_s3_object_info', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
         bucket,
         key,
-        expiresInSecs,
-        contentType: contentType || null,
       });
-      return response;
+      return info;
+    } catch (error) {
+      console.error('Failed to get S3 object info:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Size and modification time formatted in the unit base chosen in settings
+  static async formatObjectInfo(object: ObjectInfo): Promise<FormattedObjectInfo> {
+    try {
+      return await invoke<FormattedObjectInfo>('format_object_info', { object });
+    } catch (error) {
+      console.error('Failed to format object info:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Content type for picking a previewer, sniffed when the stored one is generic
+  static async detectContentType(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string
+  ): Promise<string | null> {
+    try {
+      return await invoke<string | null>('detect_s3_object_content_type', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+      });
+    } catch (error) {
+      console.error('Failed to detect content type:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Fetches bytes start..=end (inclusive) of an object, e.g. for previews
+  static async getObjectRange(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    start: number,
+    end: number
+  ): Promise<ObjectRangeResponse> {
+    try {
+      return await invoke<ObjectRangeResponse>('get_s3_object_range', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        start,
+        end,
+      });
+    } catch (error) {
+      console.error('Failed to read S3 object range:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Reads up to maxBytes (1 MiB by default) of an object as text for previews
+  static async readTextObject(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    maxBytes?: number
+  ): Promise<TextObjectResponse> {
+    try {
+      return await invoke<TextObjectResponse>('read_s3_text_object', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        maxBytes: maxBytes ?? null,
+      });
+    } catch (error) {
+      console.error('Failed to read S3 text object:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Thumbnail that fits within maxDimension pixels (256 by default)
+  static async getImageThumbnail(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    maxDimension?: number
+  ): Promise<ImageThumbnail> {
+    try {
+      return await invoke<ImageThumbnail>('get_s3_image_thumbnail', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        maxDimension: maxDimension ?? null,
+      });
+    } catch (error) {
+      console.error('Failed to get S3 image thumbnail:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async restoreObject(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    days: number,
+    tier: RestoreTier = 'Standard'
+  ): Promise<void> {
+    try {
+      await invoke('restore_s3_object', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        days,
+        tier,
+      });
+    } catch (error) {
+      console.error('Failed to restore S3 object:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async downloadObject(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    destPath: string,
+    transferId: string,
+    overwrite: boolean = false,
+    verifyChecksum: boolean = true
+  ): Promise<number> {
+    try {
+      const bytesWritten = await invoke<number>('download_s3_object', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        transferId,
+        bucket,
+        key,
+        destPath,
+        overwrite,
+        verifyChecksum,
+      });
+      return bytesWritten;
+    } catch (error) {
+      console.error('Failed to download S3 object:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async uploadObject(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    sourcePath: string,
+    transferId: string,
+    contentType?: string,
+    multipartThreshold?: number,
+    sse?: SseAlgorithm,
+    kmsKeyId?: string,
+    checksumAlgorithm: ChecksumAlgorithm = 'CRC32C',
+    // Pass ifNoneMatch '*' to fail with 'precondition_failed' instead of overwriting
+    ifMatch?: string,
+    ifNoneMatch?: string
+  ): Promise<UploadResult> {
+    try {
+      return await invoke<UploadResult>('upload_s3_object', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        transferId,
+        bucket,
+        key,
+        sourcePath,
+        contentType: contentType || null,
+        multipartThreshold: multipartThreshold || null,
+        sse: sse || null,
+        kmsKeyId: kmsKeyId || null,
+        checksumAlgorithm,
+        ifMatch: ifMatch || null,
+        ifNoneMatch: ifNoneMatch || null,
+      });
+    } catch (error) {
+      console.error('Failed to upload S3 object:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Progress arrives as transfer-progress events for `transferId`, in bytes of finished files.
+  // Pass the appearance setting `showHiddenFiles` as `includeHidden` to match the file list.
+  static async uploadDirectory(
+    connection: ConnectionConfig,
+    bucket: string,
+    destPrefix: string,
+    sourceDir: string,
+    transferId: string,
+    includeHidden = false,
+    maxConcurrency?: number
+  ): Promise<BatchResult> {
+    try {
+      return await invoke<BatchResult>('upload_s3_directory', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        transferId,
+        bucket,
+        destPrefix,
+        sourceDir,
+        includeHidden,
+        maxConcurrency: maxConcurrency || null,
+      });
+    } catch (error) {
+      console.error('Failed to upload directory:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async downloadDirectory(
+    connection: ConnectionConfig,
+    bucket: string,
+    prefix: string,
+    destDir: string,
+    transferId: string,
+    skipExisting = false,
+    maxConcurrency?: number
+  ): Promise<DirectoryDownloadResult> {
+    try {
+      return await invoke<DirectoryDownloadResult>('download_s3_directory', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        transferId,
+        bucket,
+        prefix,
+        destDir,
+        skipExisting,
+        maxConcurrency: maxConcurrency || null,
+      });
+    } catch (error) {
+      console.error('Failed to download folder:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async cancelTransfer(transferId: string): Promise<boolean> {
+    try {
+      return await invoke<boolean>('cancel_transfer', { transferId });
+    } catch (error) {
+      console.error('Failed to cancel transfer:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async enqueueTransfer(
+    connection: ConnectionConfig,
+    kind: TransferKind,
+    bucket: string,
+    key: string,
+    localPath: string,
+    overwrite = false
+  ): Promise<TransferJob> {
+    try {
+      return await invoke<TransferJob>('enqueue_transfer', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        kind,
+        bucket,
+        key,
+        localPath,
+        overwrite,
+      });
+    } catch (error) {
+      console.error('Failed to queue transfer:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async pauseTransfer(transferId: string): Promise<TransferJob> {
+    try {
+      return await invoke<TransferJob>('pause_transfer', { transferId });
+    } catch (error) {
+      console.error('Failed to pause transfer:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async resumeTransfer(connection: ConnectionConfig, transferId: string): Promise<TransferJob> {
+    try {
+      return await invoke<TransferJob>('resume_transfer', {
+        transferId,
+        connectionConfig: convertToRust.connection(connection),
+      });
+    } catch (error) {
+      console.error('Failed to resume transfer:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async retryTransfer(connection: ConnectionConfig, transferId: string): Promise<TransferJob> {
+    try {
+      return await invoke<TransferJob>('retry_transfer', {
+        transferId,
+        connectionConfig: convertToRust.connection(connection),
+      });
+    } catch (error) {
+      console.error('Failed to retry transfer:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async listTransfers(): Promise<TransferJob[]> {
+    try {
+      return await invoke<TransferJob[]>('list_transfers');
+    } catch (error) {
+      console.error('Failed to list transfers:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Tells the backend which listing refresh ticks are for; no bucket stops the ticks
+  static async setActiveView(connectionName: string, bucket?: string, prefix?: string): Promise<void> {
+    try {
+      await invoke('set_active_view', {
+        connectionName,
+        bucket: bucket || null,
+        prefix: prefix || null,
+      });
+    } catch (error) {
+      console.error('Failed to set active view:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async pauseAutoRefresh(): Promise<void> {
+    try {
+      await invoke('pause_auto_refresh');
+    } catch (error) {
+      console.error('Failed to pause auto-refresh:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async resumeAutoRefresh(): Promise<void> {
+    try {
+      await invoke('resume_auto_refresh');
+    } catch (error) {
+      console.error('Failed to resume auto-refresh:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async deleteObject(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    confirmed = false,
+    // Only deletes if the object still has this ETag
+    ifMatch?: string
+  ): Promise<void> {
+    try {
+      await invoke('delete_s3_object', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        confirmed,
+        ifMatch: ifMatch || null,
+      });
+    } catch (error) {
+      console.error('Failed to delete S3 object:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async deleteObjects(
+    connection: ConnectionConfig,
+    bucket: string,
+    keys: string[],
+    confirmed = false
+  ): Promise<DeleteResult[]> {
+    try {
+      return await invoke<DeleteResult[]>('delete_s3_objects', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        keys,
+        confirmed,
+      });
+    } catch (error) {
+      console.error('Failed to delete S3 objects:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async deleteFolder(
+    connection: ConnectionConfig,
+    bucket: string,
+    prefix: string,
+    pattern?: string,
+    confirmed = false,
+    dryRun = false
+  ): Promise<BatchResult> {
+    try {
+      return await invoke<BatchResult>('delete_s3_folder', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        prefix,
+        pattern: pattern || null,
+        confirmed,
+        dryRun,
+      });
+    } catch (error) {
+      console.error('Failed to delete S3 folder:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Moves the object under the bucket's trash prefix; returns the trashed copy's key
+  static async trashObject(connection: ConnectionConfig, bucket: string, key: string): Promise<string> {
+    try {
+      return await invoke<string>('trash_s3_object', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+      });
+    } catch (error) {
+      console.error('Failed to move S3 object to trash:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Returns the key the object was restored to
+  static async restoreTrash(connection: ConnectionConfig, bucket: string, trashKey: string): Promise<string> {
+    try {
+      return await invoke<string>('restore_s3_trash', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        trashKey,
+      });
+    } catch (error) {
+      console.error('Failed to restore S3 object from trash:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async emptyTrash(connection: ConnectionConfig, bucket: string, confirmed = false): Promise<BatchResult> {
+    try {
+      return await invoke<BatchResult>('empty_s3_trash', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        confirmed,
+      });
+    } catch (error) {
+      console.error('Failed to empty S3 trash:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // The S3 naming rules the name breaks; empty when it's valid
+  static async validateBucketName(bucket: string): Promise<string[]> {
+    try {
+      return await invoke<string[]>('validate_s3_bucket_name', { bucket });
+    } catch (error) {
+      console.error('Failed to validate bucket name:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async createBucket(
+    connection: ConnectionConfig,
+    bucket: string,
+    region?: string
+  ): Promise<void> {
+    try {
+      await invoke('create_s3_bucket', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        region: region || null,
+      });
+    } catch (error) {
+      console.error('Failed to create S3 bucket:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async deleteBucket(
+    connection: ConnectionConfig,
+    bucket: string,
+    confirmed = false
+  ): Promise<void> {
+    try {
+      await invoke('delete_s3_bucket', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        confirmed,
+      });
+    } catch (error) {
+      console.error('Failed to delete S3 bucket:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async emptyBucket(
+    connection: ConnectionConfig,
+    bucket: string,
+    includeVersions = false,
+    thenDelete = false,
+    confirmed = false
+  ): Promise<EmptyBucketResult> {
+    try {
+      return await invoke<EmptyBucketResult>('empty_s3_bucket', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        includeVersions,
+        thenDelete,
+        confirmed,
+      });
+    } catch (error) {
+      console.error('Failed to empty S3 bucket:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async createFolder(
+    connection: ConnectionConfig,
+    bucket: string,
+    folderPath: string
+  ): Promise<void> {
+    try {
+      await invoke('create_s3_folder', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        folderPath,
+      });
+    } catch (error) {
+      console.error('Failed to create S3 folder:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async generateDownloadUrl(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    expiresInSecs: number = 3600,
+    responseContentDisposition?: string,
+    responseContentType?: string,
+    // Rejects with object_not_found instead of signing a URL for a missing key
+    verifyExists = false
+  ): Promise<PresignedUrlResponse> {
+    try {
+      const response = await invoke<PresignedUrlResponse>('generate_s3_download_url', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        expiresInSecs,
+        responseContentDisposition: responseContentDisposition || null,
+        responseContentType: responseContentType || null,
+        verifyExists,
+      });
+      return response;
+    } catch (error) {
+      console.error('Failed to generate S3 download URL:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async generateUploadUrl(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    expiresInSecs: number = 3600,
+    contentType?: string
+  ): Promise<PresignedUrlResponse> {
+    try {
+      const response = await invoke<PresignedUrlResponse>('generate_s3_upload_url', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        expiresInSecs,
+        contentType: contentType || null,
+      });
+      return response;
+    } catch (error) {
+      console.error('Failed to generate S3 upload URL:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // The plain URL of an object, for publicly readable objects; computed without a request
+  static async getPublicUrl(connection: ConnectionConfig, bucket: string, key: string): Promise<string> {
+    try {
+      return await invoke<string>('get_s3_public_url', {
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+      });
+    } catch (error) {
+      console.error('Failed to build S3 public URL:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Returns a form target and the fields to submit with the file for browser uploads
+  static async generatePresignedPost(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    expiresInSecs: number = 3600,
+    maxSizeBytes?: number,
+    contentTypePrefix?: string
+  ): Promise<PresignedPostResponse> {
+    try {
+      return await invoke<PresignedPostResponse>('generate_s3_presigned_post', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        expiresInSecs,
+        maxSizeBytes: maxSizeBytes ?? null,
+        contentTypePrefix: contentTypePrefix ?? null,
+      });
+    } catch (error) {
+      console.error('Failed to generate S3 presigned POST:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async copyObject(
+    connection: ConnectionConfig,
+    sourceBucket: string,
+    sourceKey: string,
+    destBucket: string,
+    destKey: string,
+    options?: CopyOptions
+  ): Promise<void> {
+    try {
+      await invoke('copy_s3_object', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        sourceBucket,
+        sourceKey,
+        destBucket,
+        destKey,
+        options: options || null,
+      });
+    } catch (error) {
+      console.error('Failed to copy S3 object:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async copyFolder(
+    connection: ConnectionConfig,
+    sourceBucket: string,
+    sourcePrefix: string,
+    destBucket: string,
+    destPrefix: string,
+    maxConcurrency?: number,
+    dryRun = false
+  ): Promise<BatchResult> {
+    try {
+      return await invoke<BatchResult>('copy_s3_folder', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        sourceBucket,
+        sourcePrefix,
+        destBucket,
+        destPrefix,
+        maxConcurrency: maxConcurrency || null,
+        dryRun,
+      });
+    } catch (error) {
+      console.error('Failed to copy S3 folder:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async moveObject(
+    connection: ConnectionConfig,
+    sourceBucket: string,
+    sourceKey: string,
+    destBucket: string,
+    destKey: string,
+    dryRun = false
+  ): Promise<BatchResult> {
+    try {
+      return await invoke<BatchResult>('move_s3_object', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        sourceBucket,
+        sourceKey,
+        destBucket,
+        destKey,
+        dryRun,
+      });
+    } catch (error) {
+      console.error('Failed to move S3 object:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Moves the keys under destPrefix, keeping their paths below their shared folder
+  // with preserveStructure or just their names otherwise
+  static async moveObjects(
+    connection: ConnectionConfig,
+    bucket: string,
+    keys: string[],
+    destPrefix: string,
+    destBucket?: string,
+    preserveStructure = false,
+    maxConcurrency?: number
+  ): Promise<KeyMove[]> {
+    try {
+      return await invoke<KeyMove[]>('move_s3_objects', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        keys,
+        destPrefix,
+        destBucket,
+        preserveStructure,
+        maxConcurrency,
+      });
+    } catch (error) {
+      console.error('Failed to move S3 objects:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Rewrites the object's headers by copying it onto itself; returns the new ETag
+  static async updateObjectMetadata(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    metadata: Record<string, string>,
+    contentType?: string,
+    cacheControl?: string
+  ): Promise<string> {
+    try {
+      return await invoke<string>('update_s3_object_metadata', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        contentType: contentType ?? null,
+        cacheControl: cacheControl ?? null,
+        metadata,
+      });
+    } catch (error) {
+      console.error('Failed to update S3 object metadata:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Returns the storage class S3 reports after the change
+  static async setObjectStorageClass(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    storageClass: string
+  ): Promise<string> {
+    try {
+      return await invoke<string>('set_s3_object_storage_class', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        storageClass,
+      });
+    } catch (error) {
+      console.error('Failed to change S3 object storage class:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async getObjectAcl(connection: ConnectionConfig, bucket: string, key: string): Promise<ObjectAcl> {
+    try {
+      return await invoke<ObjectAcl>('get_s3_object_acl', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+      });
+    } catch (error) {
+      console.error('Failed to get S3 object ACL:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // cannedAcl is e.g. 'private' or 'public-read'
+  static async setObjectAcl(connection: ConnectionConfig, bucket: string, key: string, cannedAcl: string): Promise<void> {
+    try {
+      await invoke('set_s3_object_acl', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        cannedAcl,
+      });
+    } catch (error) {
+      console.error('Failed to set S3 object ACL:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Resolves to the object's public URL
+  static async makeObjectPublic(connection: ConnectionConfig, bucket: string, key: string): Promise<string> {
+    try {
+      return await invoke<string>('make_s3_object_public', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+      });
+    } catch (error) {
+      console.error('Failed to make S3 object public:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async getObjectRetention(connection: ConnectionConfig, bucket: string, key: string): Promise<ObjectRetention> {
+    try {
+      return await invoke<ObjectRetention>('get_s3_object_retention', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+      });
+    } catch (error) {
+      console.error('Failed to get object retention:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // retainUntil is an RFC 3339 timestamp; COMPLIANCE retention cannot be shortened or removed
+  static async setObjectRetention(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    mode: RetentionMode,
+    retainUntil: string
+  ): Promise<void> {
+    try {
+      await invoke('set_s3_object_retention', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        mode,
+        retainUntil,
+      });
+    } catch (error) {
+      console.error('Failed to set object retention:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async setObjectLegalHold(connection: ConnectionConfig, bucket: string, key: string, on: boolean): Promise<void> {
+    try {
+      await invoke('set_s3_object_legal_hold', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        on,
+      });
+    } catch (error) {
+      console.error('Failed to set legal hold:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async listMultipartUploads(
+    connection: ConnectionConfig,
+    bucket: string,
+    prefix?: string
+  ): Promise<MultipartUploadInfo[]> {
+    try {
+      return await invoke<MultipartUploadInfo[]>('list_s3_multipart_uploads', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        prefix: prefix || null,
+      });
     } catch (error) {
-      console.error('Failed to generate S3 upload URL:', error);
-      throw new Error(error as string);
+      console.error('Failed to list multipart uploads:', error);
+      throw CommandError.from(error);
     }
   }
 
-  static async copyObject(
+  static async abortMultipartUpload(
     connection: ConnectionConfig,
-    sourceBucket: string,
-    sourceKey: string,
-    destBucket: string,
-    destKey: string
+    bucket: string,
+    key: string,
+    uploadId: string
   ): Promise<void> {
     try {
-      await invoke('copy_s3_object', {
+      await invoke('abort_s3_multipart_upload', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
-        sourceBucket,
-        sourceKey,
-        destBucket,
-        destKey,
+        bucket,
+        key,
+        uploadId,
       });
     } catch (error) {
-      console.error('Failed to copy S3 object:', error);
-      throw new Error(error as string);
+      console.error('Failed to abort multipart upload:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Aborts every upload started more than olderThanHours ago
+  static async cleanupIncompleteUploads(
+    connection: ConnectionConfig,
+    bucket: string,
+    olderThanHours: number
+  ): Promise<BatchResult> {
+    try {
+      return await invoke<BatchResult>('cleanup_s3_incomplete_uploads', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        olderThanHours,
+      });
+    } catch (error) {
+      console.error('Failed to clean up incomplete uploads:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Resolves to null when the bucket has no default encryption
+  static async getBucketEncryption(connection: ConnectionConfig, bucket: string): Promise<EncryptionConfig | null> {
+    try {
+      return await invoke<EncryptionConfig | null>('get_s3_bucket_encryption', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to get bucket encryption:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async setBucketEncryption(connection: ConnectionConfig, bucket: string, config: EncryptionConfig): Promise<void> {
+    try {
+      await invoke('set_s3_bucket_encryption', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        config: { ...config, kms_key_id: config.kms_key_id || null },
+      });
+    } catch (error) {
+      console.error('Failed to set bucket encryption:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Resolves to null when website hosting isn't enabled for the bucket
+  static async getBucketWebsite(connection: ConnectionConfig, bucket: string): Promise<WebsiteConfig | null> {
+    try {
+      return await invoke<WebsiteConfig | null>('get_s3_bucket_website', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to get bucket website:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async setBucketWebsite(connection: ConnectionConfig, bucket: string, config: WebsiteConfig): Promise<void> {
+    try {
+      await invoke('set_s3_bucket_website', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        config,
+      });
+    } catch (error) {
+      console.error('Failed to set bucket website:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async deleteBucketWebsite(connection: ConnectionConfig, bucket: string): Promise<void> {
+    try {
+      await invoke('delete_s3_bucket_website', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to delete bucket website:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async getBucketLogging(connection: ConnectionConfig, bucket: string): Promise<LoggingConfig> {
+    try {
+      return await invoke<LoggingConfig>('get_s3_bucket_logging', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to get bucket logging:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // The target bucket must allow the S3 logging service to write to it
+  static async setBucketLogging(
+    connection: ConnectionConfig,
+    bucket: string,
+    enabled: boolean,
+    targetBucket?: string,
+    targetPrefix?: string
+  ): Promise<void> {
+    try {
+      await invoke('set_s3_bucket_logging', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        targetBucket: targetBucket || null,
+        targetPrefix: targetPrefix || null,
+        enabled,
+      });
+    } catch (error) {
+      console.error('Failed to set bucket logging:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async getBucketNotification(connection: ConnectionConfig, bucket: string): Promise<NotificationConfig> {
+    try {
+      return await invoke<NotificationConfig>('get_s3_bucket_notification', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to get bucket notifications:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Replaces every notification of the bucket; at least one destination is required
+  static async setBucketNotification(
+    connection: ConnectionConfig,
+    bucket: string,
+    config: NotificationConfig
+  ): Promise<void> {
+    try {
+      await invoke('set_s3_bucket_notification', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        config,
+      });
+    } catch (error) {
+      console.error('Failed to set bucket notifications:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async deleteBucketNotification(connection: ConnectionConfig, bucket: string): Promise<void> {
+    try {
+      await invoke('delete_s3_bucket_notification', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to delete bucket notifications:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async checkBucketAccess(connection: ConnectionConfig, bucket: string): Promise<BucketAccess> {
+    try {
+      return await invoke<BucketAccess>('check_s3_bucket_access', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to check S3 bucket access:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Use to disable controls the bucket or endpoint doesn't support
+  static async getBucketCapabilities(connection: ConnectionConfig, bucket: string): Promise<BucketCapabilities> {
+    try {
+      return await invoke<BucketCapabilities>('get_s3_bucket_capabilities', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to get S3 bucket capabilities:', error);
+      throw CommandError.from(error);
     }
   }
 
@@ -268,13 +1903,208 @@ export class S3Service {
   ): Promise<string> {
     try {
       const location = await invoke<string>('get_s3_bucket_location', {
+        connectionName: connection.name,
         connectionConfig: convertToRust.connection(connection),
         bucket,
       });
       return location;
     } catch (error) {
       console.error('Failed to get S3 bucket location:', error);
-      throw new Error(error as string);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async getBucketVersioning(
+    connection: ConnectionConfig,
+    bucket: string
+  ): Promise<BucketVersioningStatus> {
+    try {
+      return await invoke<BucketVersioningStatus>('get_s3_bucket_versioning', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to get S3 bucket versioning:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async setBucketVersioning(
+    connection: ConnectionConfig,
+    bucket: string,
+    enabled: boolean
+  ): Promise<void> {
+    try {
+      await invoke('set_s3_bucket_versioning', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        enabled,
+      });
+    } catch (error) {
+      console.error('Failed to set S3 bucket versioning:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async listObjectVersions(
+    connection: ConnectionConfig,
+    bucket: string,
+    prefix?: string,
+    keyMarker?: string,
+    versionIdMarker?: string
+  ): Promise<ListObjectVersionsResponse> {
+    try {
+      return await invoke<ListObjectVersionsResponse>('list_s3_object_versions', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        prefix: prefix || null,
+        keyMarker: keyMarker || null,
+        versionIdMarker: versionIdMarker || null,
+      });
+    } catch (error) {
+      console.error('Failed to list S3 object versions:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async restoreObjectVersion(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    versionId: string
+  ): Promise<void> {
+    try {
+      await invoke('restore_s3_object_version', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        versionId,
+      });
+    } catch (error) {
+      console.error('Failed to restore S3 object version:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async removeDeleteMarker(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    versionId: string
+  ): Promise<void> {
+    try {
+      await invoke('remove_s3_delete_marker', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        versionId,
+      });
+    } catch (error) {
+      console.error('Failed to remove S3 delete marker:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async getObjectTags(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string
+  ): Promise<ObjectTag[]> {
+    try {
+      return await invoke<ObjectTag[]>('get_s3_object_tags', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+      });
+    } catch (error) {
+      console.error('Failed to get S3 object tags:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async setObjectTags(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string,
+    tags: ObjectTag[]
+  ): Promise<void> {
+    try {
+      await invoke('set_s3_object_tags', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+        tags,
+      });
+    } catch (error) {
+      console.error('Failed to set S3 object tags:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async clearObjectTags(
+    connection: ConnectionConfig,
+    bucket: string,
+    key: string
+  ): Promise<void> {
+    try {
+      await invoke('clear_s3_object_tags', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        key,
+      });
+    } catch (error) {
+      console.error('Failed to clear S3 object tags:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // A bucket without tags gives an empty list
+  static async getBucketTags(connection: ConnectionConfig, bucket: string): Promise<ObjectTag[]> {
+    try {
+      return await invoke<ObjectTag[]>('get_s3_bucket_tags', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to get S3 bucket tags:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Replaces all of the bucket's tags, up to 50
+  static async setBucketTags(connection: ConnectionConfig, bucket: string, tags: ObjectTag[]): Promise<void> {
+    try {
+      await invoke('set_s3_bucket_tags', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+        tags,
+      });
+    } catch (error) {
+      console.error('Failed to set S3 bucket tags:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  static async deleteBucketTags(connection: ConnectionConfig, bucket: string): Promise<void> {
+    try {
+      await invoke('delete_s3_bucket_tags', {
+        connectionName: connection.name,
+        connectionConfig: convertToRust.connection(connection),
+        bucket,
+      });
+    } catch (error) {
+      console.error('Failed to delete S3 bucket tags:', error);
+      throw CommandError.from(error);
     }
   }
 
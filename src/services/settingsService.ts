@@ -7,13 +7,18 @@ import type {
   AppearanceSettings,
   LayoutSettings,
   PermissionsSettings,
+  ConnectionHistory,
+  ConnectionStats,
   RustAppSettings,
   RustGeneralSettings,
   RustConnectionConfig,
   RustAppearanceSettings,
   RustLayoutSettings,
   RustPermissionsSettings,
+  RustConnectionHistory,
+  RustConnectionStats,
 } from '../types/settings';
+import { CommandError } from './commandError';
 
 // Conversion utilities between camelCase (Frontend) and snake_case (Rust)
 export const convertFromRust = {
@@ -23,6 +28,12 @@ export const convertFromRust = {
     defaultDownloadLocation: rust.default_download_location,
     confirmBeforeDelete: rust.confirm_before_delete,
     showFilePreview: rust.show_file_preview,
+    logLevel: rust.log_level ?? 'info',
+    useTrash: rust.use_trash ?? false,
+    maxConcurrentTransfers: rust.max_concurrent_transfers ?? 4,
+    maxBytesPerSec: rust.max_bytes_per_sec ?? undefined,
+    sizeUnits: rust.size_units ?? 'binary',
+    autoConnectDefault: rust.auto_connect_default ?? false,
   }),
 
   connection: (rust: RustConnectionConfig): ConnectionConfig => ({
@@ -33,6 +44,26 @@ export const convertFromRust = {
     secretKey: rust.secret_key,
     region: rust.region,
     isDefault: rust.is_default,
+    forcePathStyle: rust.force_path_style ?? undefined,
+    allowInvalidCerts: rust.allow_invalid_certs ?? false,
+    sessionToken: rust.session_token ?? undefined,
+    expiration: rust.expiration ?? undefined,
+    timeoutSecs: rust.timeout_secs ?? undefined,
+    maxRetries: rust.max_retries ?? undefined,
+    proxyUrl: rust.proxy_url ?? undefined,
+    profileName: rust.profile_name ?? undefined,
+    readOnly: rust.read_only ?? false,
+    lastConnectedAt: rust.last_connected_at ?? undefined,
+    lastError: rust.last_error ?? undefined,
+    connectCount: rust.connect_count ?? 0,
+    failureCount: rust.failure_count ?? 0,
+    fallbackEndpoints: rust.fallback_endpoints ?? [],
+    anonymous: rust.anonymous ?? false,
+    requestPayer: rust.request_payer ?? false,
+    useAccelerate: rust.use_accelerate ?? false,
+    useDualstack: rust.use_dualstack ?? false,
+    useDefaultCredentialChain: rust.use_default_credential_chain ?? false,
+    maxConnections: rust.max_connections ?? undefined,
   }),
 
   appearance: (rust: RustAppearanceSettings): AppearanceSettings => ({
@@ -46,11 +77,23 @@ export const convertFromRust = {
     defaultView: rust.default_view,
     sortBy: rust.sort_by,
     sortDirection: rust.sort_direction,
+    defaultPageSize: rust.default_page_size ?? 1000,
   }),
 
   permissions: (rust: RustPermissionsSettings): PermissionsSettings => ({
     allowAnonymousUsageStats: rust.allow_anonymous_usage_stats,
     enableCaching: rust.enable_caching,
+    cacheTtlSecs: rust.cache_ttl_secs ?? 30,
+    maxPresignExpirySecs: rust.max_presign_expiry_secs ?? null,
+  }),
+
+  history: (rust: RustConnectionHistory): ConnectionHistory => ({
+    favorites: rust.favorites,
+    recentPaths: rust.recent_paths.map((recent) => ({
+      bucket: recent.bucket,
+      prefix: recent.prefix,
+      accessedAt: recent.accessed_at,
+    })),
   }),
 
   settings: (rust: RustAppSettings): AppSettings => ({
@@ -60,6 +103,9 @@ export const convertFromRust = {
     appearance: convertFromRust.appearance(rust.appearance),
     layout: convertFromRust.layout(rust.layout),
     permissions: convertFromRust.permissions(rust.permissions),
+    connectionHistory: Object.fromEntries(
+      Object.entries(rust.connection_history ?? {}).map(([name, history]) => [name, convertFromRust.history(history)])
+    ),
   }),
 };
 
@@ -70,6 +116,12 @@ export const convertToRust = {
     default_download_location: frontend.defaultDownloadLocation,
     confirm_before_delete: frontend.confirmBeforeDelete,
     show_file_preview: frontend.showFilePreview,
+    log_level: frontend.logLevel ?? 'info',
+    use_trash: frontend.useTrash ?? false,
+    max_concurrent_transfers: frontend.maxConcurrentTransfers ?? 4,
+    max_bytes_per_sec: frontend.maxBytesPerSec ?? null,
+    size_units: frontend.sizeUnits ?? 'binary',
+    auto_connect_default: frontend.autoConnectDefault ?? false,
   }),
 
   connection: (frontend: ConnectionConfig): RustConnectionConfig => ({
@@ -80,6 +132,26 @@ export const convertToRust = {
     secret_key: frontend.secretKey,
     region: frontend.region,
     is_default: frontend.isDefault,
+    force_path_style: frontend.forcePathStyle ?? null,
+    allow_invalid_certs: frontend.allowInvalidCerts ?? false,
+    session_token: frontend.sessionToken ?? null,
+    expiration: frontend.expiration ?? null,
+    timeout_secs: frontend.timeoutSecs ?? null,
+    max_retries: frontend.maxRetries ?? null,
+    proxy_url: frontend.proxyUrl ?? null,
+    profile_name: frontend.profileName ?? null,
+    read_only: frontend.readOnly ?? false,
+    last_connected_at: frontend.lastConnectedAt ?? null,
+    last_error: frontend.lastError ?? null,
+    connect_count: frontend.connectCount ?? 0,
+    failure_count: frontend.failureCount ?? 0,
+    fallback_endpoints: frontend.fallbackEndpoints ?? [],
+    anonymous: frontend.anonymous ?? false,
+    request_payer: frontend.requestPayer ?? false,
+    use_accelerate: frontend.useAccelerate ?? false,
+    use_dualstack: frontend.useDualstack ?? false,
+    use_default_credential_chain: frontend.useDefaultCredentialChain ?? false,
+    max_connections: frontend.maxConnections ?? null,
   }),
 
   appearance: (frontend: AppearanceSettings): RustAppearanceSettings => ({
@@ -93,11 +165,23 @@ export const convertToRust = {
     default_view: frontend.defaultView,
     sort_by: frontend.sortBy,
     sort_direction: frontend.sortDirection,
+    default_page_size: frontend.defaultPageSize ?? 1000,
   }),
 
   permissions: (frontend: PermissionsSettings): RustPermissionsSettings => ({
     allow_anonymous_usage_stats: frontend.allowAnonymousUsageStats,
     enable_caching: frontend.enableCaching,
+    cache_ttl_secs: frontend.cacheTtlSecs ?? 30,
+    max_presign_expiry_secs: frontend.maxPresignExpirySecs ?? null,
+  }),
+
+  history: (frontend: ConnectionHistory): RustConnectionHistory => ({
+    favorites: frontend.favorites,
+    recent_paths: frontend.recentPaths.map((recent) => ({
+      bucket: recent.bucket,
+      prefix: recent.prefix,
+      accessed_at: recent.accessedAt,
+    })),
   }),
 
   settings: (frontend: AppSettings): RustAppSettings => ({
@@ -107,6 +191,9 @@ export const convertToRust = {
     appearance: convertToRust.appearance(frontend.appearance),
     layout: convertToRust.layout(frontend.layout),
     permissions: convertToRust.permissions(frontend.permissions),
+    connection_history: Object.fromEntries(
+      Object.entries(frontend.connectionHistory ?? {}).map(([name, history]) => [name, convertToRust.history(history)])
+    ),
   }),
 };
 
@@ -137,7 +224,7 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to initialize settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -153,7 +240,7 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to get settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -168,7 +255,7 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to save settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -183,7 +270,22 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to update general settings:', error);
-      throw error;
+      throw CommandError.from(error);
+    }
+  }
+
+  // Saves the limit and applies it to transfers that are already queued
+  async setMaxConcurrentTransfers(maxConcurrentTransfers: number): Promise<AppSettings> {
+    try {
+      const rustSettings = await invoke<RustAppSettings>('set_max_concurrent_transfers', {
+        maxConcurrentTransfers,
+      });
+      this.settings = convertFromRust.settings(rustSettings);
+      this.notifyListeners();
+      return this.settings;
+    } catch (error) {
+      console.error('Failed to update transfer limit:', error);
+      throw CommandError.from(error);
     }
   }
 
@@ -198,7 +300,7 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to update appearance settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -213,7 +315,7 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to update layout settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -228,7 +330,7 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to update permissions settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -243,7 +345,7 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to add connection:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -259,7 +361,67 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to update connection:', error);
-      throw error;
+      throw CommandError.from(error);
+    }
+  }
+
+  // Adds a copy named "<name> (copy)" right after the original
+  async duplicateConnection(index: number): Promise<AppSettings> {
+    try {
+      const rustSettings = await invoke<RustAppSettings>('duplicate_connection', {
+        index,
+      });
+      this.settings = convertFromRust.settings(rustSettings);
+      this.notifyListeners();
+      return this.settings;
+    } catch (error) {
+      console.error('Failed to duplicate connection:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  // Moves the connection at fromIndex to toIndex, shifting the ones in between
+  async reorderConnections(fromIndex: number, toIndex: number): Promise<AppSettings> {
+    try {
+      const rustSettings = await invoke<RustAppSettings>('reorder_connections', {
+        fromIndex,
+        toIndex,
+      });
+      this.settings = convertFromRust.settings(rustSettings);
+      this.notifyListeners();
+      return this.settings;
+    } catch (error) {
+      console.error('Failed to reorder connections:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  async moveConnectionToTop(index: number): Promise<AppSettings> {
+    try {
+      const rustSettings = await invoke<RustAppSettings>('move_connection_to_top', {
+        index,
+      });
+      this.settings = convertFromRust.settings(rustSettings);
+      this.notifyListeners();
+      return this.settings;
+    } catch (error) {
+      console.error('Failed to move connection:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  async getConnectionStats(index: number): Promise<ConnectionStats> {
+    try {
+      const stats = await invoke<RustConnectionStats>('get_connection_stats', { index });
+      return {
+        lastConnectedAt: stats.last_connected_at ?? undefined,
+        lastError: stats.last_error ?? undefined,
+        connectCount: stats.connect_count,
+        failureCount: stats.failure_count,
+      };
+    } catch (error) {
+      console.error('Failed to get connection stats:', error);
+      throw CommandError.from(error);
     }
   }
 
@@ -273,30 +435,67 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to remove connection:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
-  async exportSettings(filePath: string): Promise<void> {
+  async addFavorite(connectionName: string, path: string): Promise<AppSettings> {
     try {
-      await invoke('export_settings', { exportPath: filePath });
+      const rustSettings = await invoke<RustAppSettings>('add_favorite', { connectionName, path });
+      this.settings = convertFromRust.settings(rustSettings);
+      this.notifyListeners();
+      return this.settings;
+    } catch (error) {
+      console.error('Failed to add favorite:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  async removeFavorite(connectionName: string, path: string): Promise<AppSettings> {
+    try {
+      const rustSettings = await invoke<RustAppSettings>('remove_favorite', { connectionName, path });
+      this.settings = convertFromRust.settings(rustSettings);
+      this.notifyListeners();
+      return this.settings;
+    } catch (error) {
+      console.error('Failed to remove favorite:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  async recordRecentPath(connectionName: string, bucket: string, prefix: string): Promise<AppSettings> {
+    try {
+      const rustSettings = await invoke<RustAppSettings>('record_recent_path', { connectionName, bucket, prefix });
+      this.settings = convertFromRust.settings(rustSettings);
+      this.notifyListeners();
+      return this.settings;
+    } catch (error) {
+      console.error('Failed to record recent path:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  async exportSettings(filePath: string, password?: string): Promise<void> {
+    try {
+      await invoke('export_settings', { exportPath: filePath, password: password || null });
     } catch (error) {
       console.error('Failed to export settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
-  async importSettings(filePath: string): Promise<AppSettings> {
+  async importSettings(filePath: string, password?: string): Promise<AppSettings> {
     try {
       const rustSettings = await invoke<RustAppSettings>('import_settings', {
         importPath: filePath,
+        password: password || null,
       });
       this.settings = convertFromRust.settings(rustSettings);
       this.notifyListeners();
       return this.settings;
     } catch (error) {
       console.error('Failed to import settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -308,7 +507,7 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to reset settings:', error);
-      throw error;
+      throw CommandError.from(error);
     }
   }
 
@@ -320,7 +519,27 @@ export class SettingsService {
       return this.settings;
     } catch (error) {
       console.error('Failed to reload settings:', error);
-      throw error;
+      throw CommandError.from(error);
+    }
+  }
+
+  // Profiles are returned for review only; nothing is saved until addConnection is called
+  async importAwsProfiles(path?: string): Promise<ConnectionConfig[]> {
+    try {
+      const rustConnections = await invoke<RustConnectionConfig[]>('import_aws_profiles', { path: path || null });
+      return rustConnections.map(convertFromRust.connection);
+    } catch (error) {
+      console.error('Failed to import AWS profiles:', error);
+      throw CommandError.from(error);
+    }
+  }
+
+  async getLogFilePath(): Promise<string> {
+    try {
+      return await invoke<string>('get_log_file_path');
+    } catch (error) {
+      console.error('Failed to get log file path:', error);
+      throw CommandError.from(error);
     }
   }
 
@@ -457,6 +676,36 @@ export function useSettings() {
     }
   }, [settingsService]);
 
+  const duplicateConnection = useCallback(async (index: number) => {
+    try {
+      setError(null);
+      await settingsService.duplicateConnection(index);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to duplicate connection');
+      throw err;
+    }
+  }, [settingsService]);
+
+  const reorderConnections = useCallback(async (fromIndex: number, toIndex: number) => {
+    try {
+      setError(null);
+      await settingsService.reorderConnections(fromIndex, toIndex);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to reorder connections');
+      throw err;
+    }
+  }, [settingsService]);
+
+  const moveConnectionToTop = useCallback(async (index: number) => {
+    try {
+      setError(null);
+      await settingsService.moveConnectionToTop(index);
+    } catch (err) {
+      setError(err instanceof Error ? err.message : 'Failed to move connection');
+      throw err;
+    }
+  }, [settingsService]);
+
   const removeConnection = useCallback(async (index: number) => {
     try {
       setError(null);
@@ -467,22 +716,25 @@ export function useSettings() {
     }
   }, [settingsService]);
 
-  const exportSettings = useCallback(async (filePath: string) => {
+  const exportSettings = useCallback(async (filePath: string, password?: string) => {
     try {
       setError(null);
-      await settingsService.exportSettings(filePath);
+      await settingsService.exportSettings(filePath, password);
     } catch (err) {
       setError(err instanceof Error ? err.message : 'Failed to export settings');
       throw err;
     }
   }, [settingsService]);
 
-  const importSettings = useCallback(async (filePath: string) => {
+  const importSettings = useCallback(async (filePath: string, password?: string) => {
     try {
       setError(null);
-      await settingsService.importSettings(filePath);
+      await settingsService.importSettings(filePath, password);
     } catch (err) {
-      setError(err instanceof Error ? err.message : 'Failed to import settings');
+      // The caller prompts for the password and retries, that's not a failure to show
+      if (!(err instanceof CommandError && err.code === 'password_required')) {
+        setError(err instanceof Error ? err.message : 'Failed to import settings');
+      }
       throw err;
     }
   }, [settingsService]);
@@ -526,6 +778,9 @@ export function useSettings() {
     updatePermissionsSettings,
     addConnection,
     updateConnection,
+    duplicateConnection,
+    reorderConnections,
+    moveConnectionToTop,
     removeConnection,
     exportSettings,
     importSettings,
@@ -536,4 +791,4 @@ export function useSettings() {
 }
 
 // Export types for backward compatibility
-export type { ConnectionConfig, AppSettings, GeneralSettings, AppearanceSettings, LayoutSettings, PermissionsSettings };
\ No newline at end of file
+export type { ConnectionConfig, AppSettings, GeneralSettings, AppearanceSettings, LayoutSettings, PermissionsSettings, ConnectionHistory };
\ No newline at end of file
@@ -1,9 +1,18 @@
+// Powers of 1024 (KiB, MiB) or 1000 (KB, MB) for displayed sizes
+export type SizeUnits = 'binary' | 'decimal';
+
 export interface GeneralSettings {
   autoRefresh: boolean;
   refreshInterval: number;
   defaultDownloadLocation: string;
   confirmBeforeDelete: boolean;
   showFilePreview: boolean;
+  logLevel?: string;
+  useTrash?: boolean;
+  maxConcurrentTransfers?: number;
+  maxBytesPerSec?: number;
+  sizeUnits?: SizeUnits;
+  autoConnectDefault?: boolean;
 }
 
 export interface ConnectionConfig {
@@ -14,8 +23,31 @@ export interface ConnectionConfig {
   secretKey: string;
   region: string;
   isDefault: boolean;
+  forcePathStyle?: boolean;
+  allowInvalidCerts?: boolean;
+  sessionToken?: string;
+  expiration?: string;
+  timeoutSecs?: number;
+  maxRetries?: number;
+  proxyUrl?: string;
+  readOnly?: boolean;
+  // Connection stats, maintained by the backend
+  lastConnectedAt?: string;
+  lastError?: string;
+  connectCount?: number;
+  failureCount?: number;
+  fallbackEndpoints?: string[];
+  anonymous?: boolean;
+  requestPayer?: boolean;
+  useAccelerate?: boolean;
+  useDualstack?: boolean;
+  useDefaultCredentialChain?: boolean;
+  profileName?: string;
+  maxConnections?: number;
 }
 
+export type ConnectionStats = Pick<ConnectionConfig, 'lastConnectedAt' | 'lastError' | 'connectCount' | 'failureCount'>;
+
 export interface AppearanceSettings {
   theme: string;
   fontSize: number;
@@ -27,11 +59,25 @@ export interface LayoutSettings {
   defaultView: string;
   sortBy: string;
   sortDirection: string;
+  defaultPageSize?: number;
 }
 
 export interface PermissionsSettings {
   allowAnonymousUsageStats: boolean;
   enableCaching: boolean;
+  cacheTtlSecs?: number;
+  maxPresignExpirySecs?: number | null;
+}
+
+export interface RecentPath {
+  bucket: string;
+  prefix: string;
+  accessedAt: string;
+}
+
+export interface ConnectionHistory {
+  favorites: string[];
+  recentPaths: RecentPath[];
 }
 
 export interface AppSettings {
@@ -41,6 +87,8 @@ export interface AppSettings {
   appearance: AppearanceSettings;
   layout: LayoutSettings;
   permissions: PermissionsSettings;
+  // Keyed by connection name
+  connectionHistory?: Record<string, ConnectionHistory>;
 }
 
 // Rust backend types (with snake_case matching Rust serialization)
@@ -50,6 +98,19 @@ export interface RustGeneralSettings {
   default_download_location: string;
   confirm_before_delete: boolean;
   show_file_preview: boolean;
+  log_level: string;
+  use_trash: boolean;
+  max_concurrent_transfers: number;
+  max_bytes_per_sec?: number | null;
+  size_units: SizeUnits;
+  auto_connect_default: boolean;
+}
+
+export interface RustConnectionStats {
+  last_connected_at?: string | null;
+  last_error?: string | null;
+  connect_count: number;
+  failure_count: number;
 }
 
 export interface RustConnectionConfig {
@@ -60,6 +121,26 @@ export interface RustConnectionConfig {
   secret_key: string;
   region: string;
   is_default: boolean;
+  force_path_style?: boolean | null;
+  allow_invalid_certs: boolean;
+  session_token?: string | null;
+  expiration?: string | null;
+  timeout_secs?: number | null;
+  max_retries?: number | null;
+  proxy_url?: string | null;
+  read_only: boolean;
+  last_connected_at?: string | null;
+  last_error?: string | null;
+  connect_count: number;
+  failure_count: number;
+  fallback_endpoints: string[];
+  anonymous: boolean;
+  request_payer: boolean;
+  use_accelerate: boolean;
+  use_dualstack: boolean;
+  use_default_credential_chain: boolean;
+  profile_name?: string | null;
+  max_connections?: number | null;
 }
 
 export interface RustAppearanceSettings {
@@ -73,11 +154,25 @@ export interface RustLayoutSettings {
   default_view: string;
   sort_by: string;
   sort_direction: string;
+  default_page_size: number;
 }
 
 export interface RustPermissionsSettings {
   allow_anonymous_usage_stats: boolean;
   enable_caching: boolean;
+  cache_ttl_secs: number;
+  max_presign_expiry_secs: number | null;
+}
+
+export interface RustRecentPath {
+  bucket: string;
+  prefix: string;
+  accessed_at: string;
+}
+
+export interface RustConnectionHistory {
+  favorites: string[];
+  recent_paths: RustRecentPath[];
 }
 
 export interface RustAppSettings {
@@ -87,4 +182,5 @@ export interface RustAppSettings {
   appearance: RustAppearanceSettings;
   layout: RustLayoutSettings;
   permissions: RustPermissionsSettings;
+  connection_history?: Record<string, RustConnectionHistory>;
 }
\ No newline at end of file